@@ -0,0 +1,123 @@
+// Every authorization check of the program goes through here, so a handler can't
+// forget half of a signer/owner check.
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{CampaignDetails, CrowdfundingError};
+
+// The roles an account can hold on a campaign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Role {
+    // The account that created the campaign, stored in `CampaignDetails::admin`.
+    Admin,
+    // An optional second signer every withdrawal needs, stored in `CampaignDetails::cosigner`.
+    Cosigner,
+    // An optional account that can end the campaign early in place of the admin, stored in
+    // `CampaignDetails::guardian`.
+    Guardian,
+    // The account an instruction names as the owner of something that doesn't hold a current
+    // campaign, like the admin of a v0 campaign or the admin stored in a voucher whose
+    // campaign was closed. It isn't stored on campaigns, see `require_authority`.
+    Authority,
+}
+
+// Makes sure `account` signed the transaction, `name` is only used in the log.
+pub(crate) fn require_signer(account: &AccountInfo, name: &str) -> ProgramResult {
+    if !account.is_signer {
+        msg!("{} should be signer", name);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+// Makes sure `account` signed the transaction and holds `role` on `campaign`.
+pub(crate) fn require_role(
+    account: &AccountInfo,
+    campaign: &CampaignDetails,
+    role: Role,
+) -> ProgramResult {
+    require_any_role(account, campaign, &[role])
+}
+
+// Like `require_role`, for an account holding any of `roles`.
+pub(crate) fn require_any_role(
+    account: &AccountInfo,
+    campaign: &CampaignDetails,
+    roles: &[Role],
+) -> ProgramResult {
+    let names = roles
+        .iter()
+        .map(|role| format!("{:?}", role))
+        .collect::<Vec<_>>()
+        .join(" or ");
+    require_signer(account, &names)?;
+
+    if !roles
+        .iter()
+        .any(|role| holds_role(account, campaign, *role))
+    {
+        msg!("Only the campaign {} can do this", names);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
+// Tells if `account` holds `role` on `campaign`, without checking it signed.
+fn holds_role(account: &AccountInfo, campaign: &CampaignDetails, role: Role) -> bool {
+    match role {
+        Role::Admin => campaign.admin == *account.key,
+        Role::Cosigner => campaign.cosigner == Some(*account.key),
+        Role::Guardian => campaign.guardian == Some(*account.key),
+        // The authority isn't stored on the campaign, it is checked by `require_authority`
+        // against the key the instruction names.
+        Role::Authority => unreachable!("the Authority role is checked by require_authority"),
+    }
+}
+
+// Makes sure `account` signed the transaction and is `authority`, see `Role::Authority`.
+pub(crate) fn require_authority(account: &AccountInfo, authority: &Pubkey) -> ProgramResult {
+    require_signer(account, &format!("{:?}", Role::Authority))?;
+    if account.key != authority {
+        msg!("Only the {:?} can do this", Role::Authority);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+// A campaign with a cosigner needs its signature too. It can be anywhere in `accounts`, the
+// instructions that need it don't have a fixed place for it.
+pub(crate) fn require_cosigner(
+    accounts: &[AccountInfo],
+    campaign: &CampaignDetails,
+) -> ProgramResult {
+    let cosigner = match campaign.cosigner {
+        Some(cosigner) => cosigner,
+        None => return Ok(()),
+    };
+    let cosigner_account = accounts
+        .iter()
+        .find(|account| *account.key == cosigner)
+        .ok_or_else(|| {
+            msg!("The campaign cosigner {} is missing", cosigner);
+            ProgramError::NotEnoughAccountKeys
+        })?;
+    require_role(cosigner_account, campaign, Role::Cosigner)
+}
+
+// A signer can never be the program itself or the campaign account, if it is the
+// transaction was put together wrong and we'd rather stop here.
+pub(crate) fn check_signer_account(
+    program_id: &Pubkey,
+    writing_account: &AccountInfo,
+    signer: &AccountInfo,
+) -> ProgramResult {
+    if signer.key == program_id || signer.key == writing_account.key {
+        msg!("A signer can't be the program or the writing_account");
+        return Err(CrowdfundingError::InvalidAccountData.into());
+    }
+    Ok(())
+}
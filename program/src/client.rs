@@ -0,0 +1,84 @@
+// Helpers for light clients that only need a field or two of a campaign account and don't
+// want to pull in the whole Borsh layout. Enable them with the `client` feature.
+//
+// A campaign account starts with the fields below, in this order, encoded by Borsh:
+//
+//   offset 0   version        u8
+//   offset 1   admin          32 bytes
+//   offset 33  name           u32 little-endian length + UTF-8 bytes
+//   ...        description    u32 little-endian length + UTF-8 bytes
+//   ...        image_link     u32 little-endian length + UTF-8 bytes
+//   ...        amount_donated u64 little-endian
+//   ...        goal           u64 little-endian
+//
+// The strings have a variable length, so the offsets after `name` are relative to the end
+// of `image_link`. These offsets are part of the account layout, never move these fields.
+
+use solana_program::{
+    program_error::ProgramError,
+    pubkey::{Pubkey, PUBKEY_BYTES},
+};
+
+use crate::{constants::CAMPAIGN_VERSION, CampaignDetails};
+
+pub const VERSION_OFFSET: usize = 0;
+pub const ADMIN_OFFSET: usize = VERSION_OFFSET + 1;
+pub const NAME_OFFSET: usize = ADMIN_OFFSET + PUBKEY_BYTES;
+// Relative to the end of `image_link`, see `fixed_fields_offset`.
+pub const AMOUNT_DONATED_RELATIVE_OFFSET: usize = 0;
+pub const GOAL_RELATIVE_OFFSET: usize = AMOUNT_DONATED_RELATIVE_OFFSET + 8;
+
+// Decodes a whole campaign account, with the same checks the program runs before using one:
+// the data must be exactly one campaign, of the current version and with an admin.
+// Anything else, including older versions, is rejected with InvalidAccountData or
+// UninitializedAccount.
+// Campaign accounts carry no checksum. Only the program can write to the accounts it owns,
+// so the bytes can't be tampered with on chain, and a truncated or garbled copy fails the
+// exact Borsh decode. A checksum would only cost every write a hash for nothing.
+pub fn decode_campaign(data: &[u8]) -> Result<CampaignDetails, ProgramError> {
+    CampaignDetails::from_bytes(data)
+}
+
+// Returns the admin of the campaign, or None if `data` isn't a campaign we can read.
+pub fn read_admin(data: &[u8]) -> Option<Pubkey> {
+    check_version(data)?;
+    let bytes = data.get(ADMIN_OFFSET..ADMIN_OFFSET + PUBKEY_BYTES)?;
+    Some(Pubkey::new(bytes))
+}
+
+// Returns the amount donated to the campaign, or None if `data` isn't a campaign we can read.
+pub fn read_amount_donated(data: &[u8]) -> Option<u64> {
+    read_u64(
+        data,
+        fixed_fields_offset(data)? + AMOUNT_DONATED_RELATIVE_OFFSET,
+    )
+}
+
+// Returns the goal of the campaign, or None if `data` isn't a campaign we can read.
+pub fn read_goal(data: &[u8]) -> Option<u64> {
+    read_u64(data, fixed_fields_offset(data)? + GOAL_RELATIVE_OFFSET)
+}
+
+// Older layouts put the fields elsewhere, so we only read accounts of the current version.
+fn check_version(data: &[u8]) -> Option<()> {
+    match data.get(VERSION_OFFSET)? {
+        &CAMPAIGN_VERSION => Some(()),
+        _ => None,
+    }
+}
+
+// Skips the three strings and returns the offset of `amount_donated`.
+fn fixed_fields_offset(data: &[u8]) -> Option<usize> {
+    check_version(data)?;
+    let mut offset = NAME_OFFSET;
+    for _ in 0..3 {
+        let len = u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?);
+        offset = offset.checked_add(4)?.checked_add(len as usize)?;
+    }
+    Some(offset)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset.checked_add(8)?)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
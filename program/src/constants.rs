@@ -0,0 +1,100 @@
+// Every tunable value of the program lives here, so handlers and clients refer to the
+// same named limits instead of repeating numbers.
+
+use solana_program::native_token::LAMPORTS_PER_SOL;
+
+// The layout version written in the first byte of every campaign account.
+pub const CAMPAIGN_VERSION: u8 = 1;
+
+// Upper bound for goals and hard caps, nobody should be raising more than this.
+pub const MAX_GOAL: u64 = 10_000_000 * LAMPORTS_PER_SOL;
+
+// What a new campaign must hold on top of its rent, so creating empty spam campaigns isn't free.
+pub const MIN_INITIAL_FUNDING: u64 = LAMPORTS_PER_SOL / 100;
+
+// The cluster clock comes from the validators' stake-weighted timestamps and can be a few
+// seconds off from wall time. A donation landing this many seconds after the deadline is
+// still accepted, so a small skew right at the boundary doesn't bounce an honest donor.
+pub const CLOCK_TOLERANCE_SECS: i64 = 30;
+
+// Seed of the PDA keeping the latest donors of a campaign, next to the campaign key.
+pub const RECENT_DONORS_SEED: &[u8] = b"recent_donors";
+
+// How many donors the recent donors PDA remembers before evicting the oldest.
+pub const RECENT_DONORS_LEN: usize = 10;
+
+// How many donors a campaign admin can block, each one makes the campaign account bigger.
+pub const MAX_BLOCKED_DONORS: usize = 32;
+
+// Basis points are hundredths of a percent, 10_000 of them make the whole amount.
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+// The biggest rent buffer a campaign can keep, 10% of its balance.
+pub const MAX_RENT_BUFFER_BPS: u16 = 1_000;
+
+// First byte of a versioned instruction. It can't collide with a tag, tags are small indexes.
+pub const VERSIONED_INSTRUCTION_MARKER: u8 = 0xff;
+
+// The instruction format version this program understands, it follows the marker byte.
+pub const INSTRUCTION_FORMAT_VERSION: u8 = 1;
+
+// The longest thank-you note an admin can set, in bytes.
+pub const MAX_THANK_YOU_LEN: usize = 280;
+
+// Lower bounds of the donation histogram buckets above the first one, in lamports:
+// under 1 SOL, 1 to 10 SOL, 10 to 100 SOL and 100 SOL or more.
+pub const DONATION_BUCKET_BOUNDS: [u64; DONATION_BUCKETS - 1] = [
+    LAMPORTS_PER_SOL,
+    10 * LAMPORTS_PER_SOL,
+    100 * LAMPORTS_PER_SOL,
+];
+
+// The number of buckets in the donation histogram.
+pub const DONATION_BUCKETS: usize = 4;
+
+// Seed of the voucher PDAs, next to the campaign key and the voucher id.
+pub const VOUCHER_SEED: &[u8] = b"voucher";
+
+// The longest vesting schedule a campaign can use, about 5 years.
+pub const MAX_VESTING_DURATION_SECS: i64 = 5 * 365 * 24 * 60 * 60;
+
+// The widest "ending soon" window a campaign can ask for before its deadline, 30 days.
+pub const MAX_ENDING_SOON_WINDOW_SECS: i64 = 30 * 24 * 60 * 60;
+
+// The most campaigns a single FinalizeExpired instruction goes through.
+pub const MAX_FINALIZE_BATCH: usize = 16;
+
+// Seed of the per-epoch donations PDA, next to the campaign key.
+pub const EPOCH_DONATIONS_SEED: &[u8] = b"epoch_donations";
+
+// How many epochs the per-epoch donations PDA remembers, older epochs get overwritten.
+pub const EPOCH_DONATIONS_LEN: usize = 16;
+
+// What Shortfall returns for a campaign without a goal, there is nothing to fall short of.
+pub const NO_GOAL_SHORTFALL: u64 = u64::MAX;
+
+// Campaigns with sweep_dust set never keep less than this many withdrawable lamports after
+// a withdrawal, the remainder goes to the admin with it.
+pub const DUST_THRESHOLD: u64 = 10_000;
+
+// Seed of the campaign directory PDA, and with a little-endian u64 page index, of its pages.
+pub const DIRECTORY_SEED: &[u8] = b"directory";
+
+// How many campaigns a directory page lists, each one is a pubkey in the page account.
+pub const DIRECTORY_PAGE_LEN: usize = 32;
+
+// The longest purpose note an admin can attach to a withdrawal, in bytes.
+pub const MAX_WITHDRAW_NOTE_LEN: usize = 140;
+
+// The biggest share of a donation a campaign can hand to referrers, 20%.
+pub const MAX_REFERRAL_BPS: u16 = 2_000;
+
+// Every donation adds this many points to the campaign activity score.
+pub const ACTIVITY_POINTS_PER_DONATION: u64 = 1_000;
+
+// The activity score never goes above this, so a burst of tiny donations can't pin a
+// campaign at the top of the rankings for long.
+pub const MAX_ACTIVITY_SCORE: u64 = 1_000 * ACTIVITY_POINTS_PER_DONATION;
+
+// The activity score halves every this many seconds without donations, 7 days.
+pub const ACTIVITY_HALF_LIFE_SECS: i64 = 7 * 24 * 60 * 60;
@@ -1,71 +1,728 @@
 // First we include what we are going to need in our program.
 
+mod acl;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod constants;
+#[cfg(test)]
+mod tests;
+
+use acl::{
+    check_signer_account, require_any_role, require_authority, require_cosigner, require_role,
+    require_signer, Role,
+};
 use borsh::{BorshDeserialize, BorshSerialize};
+use constants::{
+    ACTIVITY_HALF_LIFE_SECS, ACTIVITY_POINTS_PER_DONATION, BPS_DENOMINATOR, CAMPAIGN_VERSION,
+    CLOCK_TOLERANCE_SECS, DIRECTORY_PAGE_LEN, DIRECTORY_SEED, DONATION_BUCKETS,
+    DONATION_BUCKET_BOUNDS, DUST_THRESHOLD, EPOCH_DONATIONS_LEN, EPOCH_DONATIONS_SEED,
+    INSTRUCTION_FORMAT_VERSION, MAX_ACTIVITY_SCORE, MAX_BLOCKED_DONORS,
+    MAX_ENDING_SOON_WINDOW_SECS, MAX_FINALIZE_BATCH, MAX_GOAL, MAX_REFERRAL_BPS,
+    MAX_RENT_BUFFER_BPS, MAX_THANK_YOU_LEN, MAX_VESTING_DURATION_SECS, MAX_WITHDRAW_NOTE_LEN,
+    MIN_INITIAL_FUNDING, NO_GOAL_SHORTFALL, RECENT_DONORS_LEN, RECENT_DONORS_SEED,
+    VERSIONED_INSTRUCTION_MARKER, VOUCHER_SEED,
+};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint,
-    entrypoint::ProgramResult,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
+    instruction::{AccountMeta, Instruction},
     msg,
+    program::{invoke, invoke_signed, set_return_data},
     program_error::ProgramError,
-    pubkey::Pubkey,
+    pubkey::{Pubkey, PUBKEY_BYTES},
     rent::Rent,
+    system_instruction, system_program,
     sysvar::Sysvar,
 };
 
 // Every solana program has one entry point
 // It should take in program_id, accounts, instruction_data as parameters.
-fn process_instruction(
+pub fn process_instruction(
     // program id is the id of this program on the solana network.
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     // This is the data we want to process our instruction for, it is a list of 8 bitunsigned integers(0..255).
     instruction_data: &[u8],
 ) -> ProgramResult {
-    if instruction_data.len() == 0 {
-        return Err(ProgramError::InvalidInstructionData);
-    }
+    // We decode the instruction first, so every handler receives its payload already parsed.
+    let instruction = CrowdfundingInstruction::try_from(instruction_data)?;
 
-    if instruction_data[0] == 0 {
-        return create_campaign(
-            program_id,
-            accounts,
-            // we pass a reference to slice of [instruction_data], we do not want the first element in any of our functions.
-            &instruction_data[1..instruction_data.len()],
-        );
-    } else if instruction_data[0] == 1 {
-        return withdraw(
-            program_id,
-            accounts,
-            &instruction_data[1..instruction_data.len()],
-        );
-    } else if instruction_data[0] == 2 {
-        return donate(
-            program_id,
-            accounts,
-            &instruction_data[1..instruction_data.len()],
-        );
+    match instruction {
+        CrowdfundingInstruction::CreateCampaign(input_data) => {
+            create_campaign(program_id, accounts, input_data)
+        }
+        CrowdfundingInstruction::Withdraw(input_data) => {
+            withdraw(program_id, accounts, input_data, false).map(|_| ())
+        }
+        CrowdfundingInstruction::Donate => donate(program_id, accounts, None),
+        CrowdfundingInstruction::MigrateV0ToV1 => migrate_v0_to_v1(program_id, accounts),
+        CrowdfundingInstruction::RaiseHardCap(input_data) => {
+            raise_hard_cap(program_id, accounts, input_data)
+        }
+        CrowdfundingInstruction::ForkCampaign(input_data) => {
+            fork_campaign(program_id, accounts, input_data)
+        }
+        CrowdfundingInstruction::RecentDonors => recent_donors(program_id, accounts),
+        CrowdfundingInstruction::WithdrawOrMax(input_data) => {
+            withdraw(program_id, accounts, input_data, true).map(|_| ())
+        }
+        CrowdfundingInstruction::BlockDonor(input_data) => {
+            set_donor_blocked(program_id, accounts, input_data, true)
+        }
+        CrowdfundingInstruction::UnblockDonor(input_data) => {
+            set_donor_blocked(program_id, accounts, input_data, false)
+        }
+        CrowdfundingInstruction::Status => status(program_id, accounts),
+        CrowdfundingInstruction::Prune => prune(program_id, accounts),
+        CrowdfundingInstruction::SetThankYou(input_data) => {
+            set_thank_you(program_id, accounts, input_data)
+        }
+        CrowdfundingInstruction::GetThankYou => get_thank_you(program_id, accounts),
+        CrowdfundingInstruction::DonationHistogram => donation_histogram(program_id, accounts),
+        CrowdfundingInstruction::CreateVoucher(input_data) => {
+            create_voucher(program_id, accounts, input_data)
+        }
+        CrowdfundingInstruction::RedeemVoucher(input_data) => {
+            redeem_voucher(program_id, accounts, input_data)
+        }
+        CrowdfundingInstruction::CreateAndDonate(input_data) => {
+            donate(program_id, accounts, Some(input_data.amount))
+        }
+        CrowdfundingInstruction::FinalizeExpired => finalize_expired(program_id, accounts),
+        CrowdfundingInstruction::Shortfall => shortfall(program_id, accounts),
+        CrowdfundingInstruction::Reconcile => reconcile(program_id, accounts),
+        CrowdfundingInstruction::SetMetadataHash(input_data) => {
+            set_metadata_hash(program_id, accounts, input_data)
+        }
+        CrowdfundingInstruction::RegisterCampaign => register_campaign(program_id, accounts),
+        CrowdfundingInstruction::DirectoryPage(input_data) => {
+            directory_page(program_id, accounts, input_data)
+        }
+        CrowdfundingInstruction::WithdrawWithNote(input_data) => {
+            withdraw_with_note(program_id, accounts, input_data)
+        }
+        CrowdfundingInstruction::DonateWithReferrer => donate_with_referrer(program_id, accounts),
+        CrowdfundingInstruction::ActivityScore => activity_score(program_id, accounts),
+        CrowdfundingInstruction::EndNow => end_now(program_id, accounts),
+        CrowdfundingInstruction::CloseVoucher(input_data) => {
+            close_voucher(program_id, accounts, input_data)
+        }
     }
-
-    msg!("Didn't find the entrypoint required");
-    Err(ProgramError::InvalidInstructionData)
 }
 
 // Then we call the entry point macro to add `process_instruction` as our entry point to our program.
 entrypoint!(process_instruction);
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-struct CampaignDetails {
+// The errors specific to our program, they reach the client as `ProgramError::Custom(code)`.
+// Clients match on these codes, so they are pinned: never renumber or reuse one, new
+// errors always take the next free code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrowdfundingError {
+    // The writing account isn't exactly the size of the campaign it should hold.
+    AccountSizeMismatch = 0,
+    // The writing account doesn't hold rent plus MIN_INITIAL_FUNDING at creation.
+    InsufficientInitialFunding = 1,
+    // An account passed as a signer is the program or the campaign itself.
+    InvalidAccountData = 2,
+    // The instruction tag is unknown or its payload doesn't decode.
+    InvalidInstruction = 3,
+    // The campaign deadline has passed.
+    CampaignEnded = 4,
+    // An account would grow by more than a single instruction is allowed to.
+    ReallocTooLarge = 5,
+    // The campaign was created immutable, its settings can't be edited.
+    CampaignImmutable = 6,
+    // The admin blocked this donor from donating to the campaign.
+    DonorBlocked = 7,
+    // The campaign already blocks MAX_BLOCKED_DONORS donors.
+    BlockListFull = 8,
+    // The campaign is still running or received donations, so it can't be pruned.
+    CampaignNotPrunable = 9,
+    // The instruction uses a format version this program doesn't know.
+    UnsupportedInstructionVersion = 10,
+    // A single donation is above the campaign max_per_tx.
+    DonationTooLarge = 11,
+    // The voucher was already redeemed.
+    VoucherAlreadyRedeemed = 12,
+    // The withdrawal goes beyond what the vesting schedule released so far.
+    ExceedsVestedAmount = 13,
+    // The campaign can't be finalized before its deadline.
+    CampaignNotExpired = 14,
+    // A donation must bring at least one lamport.
+    InvalidAmount = 15,
+    // The campaign holds fewer lamports than its donations, withdrawals and rent account for.
+    BalanceShortfall = 16,
+    // An account receiving lamports wasn't passed as writable.
+    AccountNotWritable = 17,
+    // The campaign outcome was recorded by FinalizeExpired, it takes no more donations.
+    CampaignFinalized = 18,
+}
+
+impl From<CrowdfundingError> for ProgramError {
+    fn from(e: CrowdfundingError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+// Lets clients turn the code of a `ProgramError::Custom` back into the error.
+impl TryFrom<u32> for CrowdfundingError {
+    type Error = ProgramError;
+
+    fn try_from(code: u32) -> Result<Self, Self::Error> {
+        use CrowdfundingError::*;
+        Ok(match code {
+            0 => AccountSizeMismatch,
+            1 => InsufficientInitialFunding,
+            2 => InvalidAccountData,
+            3 => InvalidInstruction,
+            4 => CampaignEnded,
+            5 => ReallocTooLarge,
+            6 => CampaignImmutable,
+            7 => DonorBlocked,
+            8 => BlockListFull,
+            9 => CampaignNotPrunable,
+            10 => UnsupportedInstructionVersion,
+            11 => DonationTooLarge,
+            12 => VoucherAlreadyRedeemed,
+            13 => ExceedsVestedAmount,
+            14 => CampaignNotExpired,
+            15 => InvalidAmount,
+            16 => BalanceShortfall,
+            17 => AccountNotWritable,
+            18 => CampaignFinalized,
+            _ => return Err(ProgramError::Custom(code)),
+        })
+    }
+}
+
+// Every instruction our program understands. On the wire the first byte is the tag
+// (the variant index) and the rest is the Borsh encoded payload, if the variant has one.
+// The tag can be preceded by a format header, see `strip_format_header`.
+#[derive(Debug)]
+enum CrowdfundingInstruction {
+    // 0: create a campaign in the writing account.
+    CreateCampaign(CampaignDetails),
+    // 1: the admin withdraws lamports from the campaign.
+    Withdraw(WithdrawRequest),
+    // 2: sweep the donator program account into the campaign.
+    Donate,
+    // 3: rewrite a campaign stored in the pre-versioning layout.
+    MigrateV0ToV1,
+    // 4: the admin raises the campaign hard cap.
+    RaiseHardCap(RaiseHardCapRequest),
+    // 5: the admin moves part of the funds into a new child campaign.
+    ForkCampaign(ForkCampaignRequest),
+    // 6: read the latest donors of a campaign, returned as a Borsh `Vec<Pubkey>`.
+    RecentDonors,
+    // 7: like Withdraw, but withdraws everything available when the amount is too high.
+    WithdrawOrMax(WithdrawRequest),
+    // 8: the admin stops a donor from donating to the campaign.
+    BlockDonor(DonorRequest),
+    // 9: the admin lets a blocked donor donate again.
+    UnblockDonor(DonorRequest),
+    // 10: read the campaign status, returned as a single `CampaignStatus` byte.
+    Status,
+    // 11: anyone closes an expired campaign that never received a donation.
+    Prune,
+    // 12: the admin sets the thank-you note shown to donors.
+    SetThankYou(ThankYouRequest),
+    // 13: read the thank-you note, returned as its UTF-8 bytes.
+    GetThankYou,
+    // 14: read the donation size histogram, returned as Borsh `[u32; DONATION_BUCKETS]`.
+    DonationHistogram,
+    // 15: the admin funds a voucher PDA that any donor can redeem once.
+    CreateVoucher(CreateVoucherRequest),
+    // 16: a donor redeems a voucher, its lamports are donated to the campaign.
+    RedeemVoucher(VoucherRequest),
+    // 17: like Donate, but first creates and funds the donator program account from the donator.
+    CreateAndDonate(CreateAndDonateRequest),
+    // 18: anyone records the final status of a batch of expired campaigns. The keeper signs
+    // and pays the extra rent, followed by the system program and the campaigns.
+    FinalizeExpired,
+    // 19: read how many lamports are still missing to reach the goal, as a little-endian u64.
+    Shortfall,
+    // 20: check the campaign balance against its books, the surplus is returned as a
+    // little-endian u64.
+    Reconcile,
+    // 21: the admin updates the metadata hash after changing the off-chain metadata.
+    SetMetadataHash(MetadataHashRequest),
+    // 22: the admin adds the campaign to the campaign directory.
+    RegisterCampaign,
+    // 23: read a page of the campaign directory, returned as a Borsh `DirectoryPageView`.
+    DirectoryPage(DirectoryPageRequest),
+    // 24: like Withdraw, with a note stating what the lamports are for.
+    WithdrawWithNote(WithdrawWithNoteRequest),
+    // 25: like Donate, but the campaign referral_bps share goes to a referrer.
+    DonateWithReferrer,
+    // 26: read the campaign activity score, returned as a little-endian u64.
+    ActivityScore,
+    // 27: the admin or the guardian ends the campaign right away.
+    EndNow,
+    // 28: the admin closes a voucher nobody redeemed and gets its lamports back.
+    CloseVoucher(VoucherRequest),
+}
+
+impl TryFrom<&[u8]> for CrowdfundingInstruction {
+    type Error = ProgramError;
+
+    fn try_from(instruction_data: &[u8]) -> Result<Self, Self::Error> {
+        let instruction_data = strip_format_header(instruction_data)?;
+
+        let (&tag, payload) = instruction_data.split_first().ok_or_else(|| {
+            msg!("Instruction data is empty");
+            CrowdfundingError::InvalidInstruction
+        })?;
+
+        let instruction = match tag {
+            0 => Self::CreateCampaign(decode_payload(tag, payload)?),
+            1 => Self::Withdraw(decode_payload(tag, payload)?),
+            2 => Self::Donate,
+            3 => Self::MigrateV0ToV1,
+            4 => Self::RaiseHardCap(decode_payload(tag, payload)?),
+            5 => Self::ForkCampaign(decode_payload(tag, payload)?),
+            6 => Self::RecentDonors,
+            7 => Self::WithdrawOrMax(decode_payload(tag, payload)?),
+            8 => Self::BlockDonor(decode_payload(tag, payload)?),
+            9 => Self::UnblockDonor(decode_payload(tag, payload)?),
+            10 => Self::Status,
+            11 => Self::Prune,
+            12 => Self::SetThankYou(decode_payload(tag, payload)?),
+            13 => Self::GetThankYou,
+            14 => Self::DonationHistogram,
+            15 => Self::CreateVoucher(decode_payload(tag, payload)?),
+            16 => Self::RedeemVoucher(decode_payload(tag, payload)?),
+            17 => Self::CreateAndDonate(decode_payload(tag, payload)?),
+            18 => Self::FinalizeExpired,
+            19 => Self::Shortfall,
+            20 => Self::Reconcile,
+            21 => Self::SetMetadataHash(decode_payload(tag, payload)?),
+            22 => Self::RegisterCampaign,
+            23 => Self::DirectoryPage(decode_payload(tag, payload)?),
+            24 => Self::WithdrawWithNote(decode_payload(tag, payload)?),
+            25 => Self::DonateWithReferrer,
+            26 => Self::ActivityScore,
+            27 => Self::EndNow,
+            28 => Self::CloseVoucher(decode_payload(tag, payload)?),
+            _ => {
+                msg!(
+                    "Didn't find the entrypoint required, unknown instruction tag {}",
+                    tag
+                );
+                return Err(CrowdfundingError::InvalidInstruction.into());
+            }
+        };
+
+        // Instructions without a payload must not carry any trailing bytes either.
+        if matches!(
+            instruction,
+            Self::Donate
+                | Self::MigrateV0ToV1
+                | Self::RecentDonors
+                | Self::Status
+                | Self::Prune
+                | Self::GetThankYou
+                | Self::DonationHistogram
+                | Self::FinalizeExpired
+                | Self::Shortfall
+                | Self::Reconcile
+                | Self::RegisterCampaign
+                | Self::DonateWithReferrer
+                | Self::ActivityScore
+                | Self::EndNow
+        ) && !payload.is_empty()
+        {
+            msg!("Instruction tag {} doesn't take a payload", tag);
+            return Err(CrowdfundingError::InvalidInstruction.into());
+        }
+
+        Ok(instruction)
+    }
+}
+
+// Versioned instructions start with VERSIONED_INSTRUCTION_MARKER and a format version byte,
+// followed by the tag and payload. Anything else is the original unversioned format, which
+// we still accept as is. This returns the tag and payload part.
+fn strip_format_header(instruction_data: &[u8]) -> Result<&[u8], ProgramError> {
+    match instruction_data {
+        [VERSIONED_INSTRUCTION_MARKER, INSTRUCTION_FORMAT_VERSION, rest @ ..] => Ok(rest),
+        [VERSIONED_INSTRUCTION_MARKER, version, ..] => {
+            msg!("Unsupported instruction format version {}", version);
+            Err(CrowdfundingError::UnsupportedInstructionVersion.into())
+        }
+        [VERSIONED_INSTRUCTION_MARKER] => {
+            msg!("Versioned instruction is missing its format version");
+            Err(CrowdfundingError::InvalidInstruction.into())
+        }
+        _ => Ok(instruction_data),
+    }
+}
+
+// Decodes the Borsh payload of an instruction, reporting the tag it belonged to on failure.
+fn decode_payload<T: BorshDeserialize>(tag: u8, payload: &[u8]) -> Result<T, ProgramError> {
+    T::try_from_slice(payload).map_err(|_| {
+        msg!("Invalid payload for instruction tag {}", tag);
+        CrowdfundingError::InvalidInstruction.into()
+    })
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
+pub struct CampaignDetails {
+    // Always CAMPAIGN_VERSION for accounts written by this program.
+    pub version: u8,
     pub admin: Pubkey,
     pub name: String,
     pub description: String,
     pub image_link: String,
     pub amount_donated: u64,
+    // The amount the admin is trying to raise, 0 means the campaign has no goal.
+    pub goal: u64,
+    // An optional second tier above the goal, 0 means there is no stretch goal.
+    pub stretch_goal: u64,
+    // Donations can't push amount_donated above this, 0 means the campaign is uncapped.
+    pub hard_cap: u64,
+    // Unix timestamp after which donations stop, 0 means the campaign never ends.
+    pub deadline: i64,
+    // Chosen at creation, when true none of the campaign settings can be edited afterwards.
+    // Donating and withdrawing still work.
+    pub immutable: bool,
+    // Donors the admin doesn't want donations from, at most MAX_BLOCKED_DONORS of them.
+    pub blocked_donors: Vec<Pubkey>,
+    // Share of the balance, in basis points, that is kept on top of the rent on every
+    // withdrawal, in case the rent goes up during a long campaign.
+    pub rent_buffer_bps: u16,
+    // A short note from the admin that clients show to donors after they donate.
+    pub thank_you: String,
+    // How many donations fell in each size bucket, see DONATION_BUCKET_BOUNDS.
+    pub donation_histogram: [u32; DONATION_BUCKETS],
+    // The most a single donation can bring, to catch fat-finger mistakes. None means no limit.
+    pub max_per_tx: Option<u64>,
+    // Unix timestamp of the creation, set by the program.
+    pub created_at: i64,
+    // Donations unlock linearly for withdrawal over this many seconds after created_at,
+    // 0 means they can be withdrawn right away.
+    pub vesting_duration: i64,
+    // Everything the admin withdrew so far, kept by the program.
+    pub total_withdrawn: u64,
+    // An optional external program that must approve every donation and withdrawal,
+    // see `run_validator`.
+    pub validator_program: Option<Pubkey>,
+    // The first donation this many seconds or less before the deadline logs CF:ENDING_SOON,
+    // 0 turns the log off.
+    pub ending_soon_window: i64,
+    // Set by the program once CF:ENDING_SOON was logged, so it is only logged once.
+    pub ending_soon_notified: bool,
+    // The outcome recorded by FinalizeExpired, None until the campaign is finalized.
+    pub final_status: Option<CampaignStatus>,
+    // A hash of the off-chain metadata the campaign links to, so clients can tell it wasn't
+    // swapped behind the admin's back. All zeros means the campaign doesn't publish one.
+    pub metadata_hash: [u8; 32],
+    // When a withdrawal would leave less than DUST_THRESHOLD withdrawable lamports behind,
+    // they are withdrawn as well instead of staying stranded in the campaign.
+    pub sweep_dust: bool,
+    // The position of the campaign in the campaign directory, None until it is registered.
+    pub directory_index: Option<u64>,
+    // The share of a DonateWithReferrer donation going to the referrer, in basis points.
+    pub referral_bps: u16,
+    // The activity score as of activity_updated_at, see `activity_score`.
+    pub activity_score: u64,
+    pub activity_updated_at: i64,
+    // When set, every withdrawal must be signed by this account as well as the admin.
+    pub cosigner: Option<Pubkey>,
+    // When set, this account can end the campaign early like the admin can.
+    pub guardian: Option<Pubkey>,
+}
+
+impl CampaignDetails {
+    // The number of bytes this campaign takes once serialized, which is the exact
+    // size the writing account must have.
+    fn space(&self) -> Result<usize, ProgramError> {
+        Ok(self.try_to_vec()?.len())
+    }
+
+    // Reads the campaign stored in `account`, making sure it really is one of our campaigns
+    // before any handler touches it: owned by the program, fully written, current version,
+    // with an admin and exactly the size of its content.
+    fn load(program_id: &Pubkey, account: &AccountInfo) -> Result<Self, ProgramError> {
+        if account.owner != program_id {
+            msg!("writing_account isn't owned by program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let campaign_data = Self::from_bytes(&account.data.borrow())?;
+
+        if account.data_len() != campaign_data.space()? {
+            msg!("writing_account data length doesn't match the campaign size");
+            return Err(CrowdfundingError::AccountSizeMismatch.into());
+        }
+
+        Ok(campaign_data)
+    }
+
+    // Decodes a campaign of the current version with an admin from the raw account data.
+    // This is the part of `load` that doesn't need the account, clients use it too.
+    fn from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        let campaign_data = Self::try_from_slice(data).map_err(|_| {
+            msg!("writing_account doesn't hold a valid campaign");
+            ProgramError::InvalidAccountData
+        })?;
+
+        if campaign_data.version != CAMPAIGN_VERSION {
+            msg!("Unsupported campaign version {}", campaign_data.version);
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if campaign_data.admin == Pubkey::default() {
+            msg!("writing_account holds a campaign without admin");
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Ok(campaign_data)
+    }
+
+    // The lamports the admin can take out of `account`: everything but the rent-exempt
+    // balance and the rent buffer.
+    fn withdrawable(&self, account: &AccountInfo) -> Result<u64, ProgramError> {
+        let balance = account.lamports();
+        let rent_exemption = Rent::get()?.minimum_balance(account.data_len());
+        let rent_buffer =
+            (balance as u128 * self.rent_buffer_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        Ok(balance
+            .saturating_sub(rent_exemption)
+            .saturating_sub(rent_buffer))
+    }
+
+    // Checks `donor` can give `amount` lamports to the campaign right now and adds them to
+    // amount_donated. The caller moves the lamports and saves the campaign.
+    fn accept_donation(&mut self, donor: &Pubkey, amount: u64) -> ProgramResult {
+        if amount == 0 {
+            msg!("A donation must be greater than zero");
+            return Err(CrowdfundingError::InvalidAmount.into());
+        }
+        // A finalized campaign is past its deadline too, but its outcome is recorded and the
+        // donations after it wouldn't count in it, so we reject it first with its own error.
+        if self.final_status.is_some() {
+            msg!("This campaign is finalized");
+            return Err(CrowdfundingError::CampaignFinalized.into());
+        }
+        let now = Clock::get()?.unix_timestamp;
+        if is_past_deadline(self.deadline, now) {
+            msg!("This campaign has ended");
+            return Err(CrowdfundingError::CampaignEnded.into());
+        }
+        if self.blocked_donors.contains(donor) {
+            msg!("This donor is blocked by the campaign admin");
+            return Err(CrowdfundingError::DonorBlocked.into());
+        }
+        self.check_donation_limits(amount)?;
+        let previous_amount = self.amount_donated;
+        self.amount_donated = previous_amount
+            .checked_add(amount)
+            .ok_or(ProgramError::InvalidArgument)?;
+
+        // Emit a log the first time each tier is crossed so indexers can pick up the milestones.
+        if crossed(previous_amount, self.amount_donated, self.goal) {
+            msg!("CF:GOAL_REACHED {}", self.goal);
+        }
+        if crossed(previous_amount, self.amount_donated, self.stretch_goal) {
+            msg!("CF:STRETCH_GOAL_REACHED {}", self.stretch_goal);
+        }
+        if self.ending_soon_window != 0
+            && self.deadline != 0
+            && !self.ending_soon_notified
+            && self.deadline.saturating_sub(now) <= self.ending_soon_window
+        {
+            msg!("CF:ENDING_SOON {}", self.deadline);
+            self.ending_soon_notified = true;
+        }
+
+        self.record_donation_size(amount)?;
+
+        self.activity_score = self
+            .activity_score(now)
+            .saturating_add(ACTIVITY_POINTS_PER_DONATION)
+            .min(MAX_ACTIVITY_SCORE);
+        self.activity_updated_at = now;
+
+        Ok(())
+    }
+
+    // The activity score at `now`. It only moves on donations and halves every
+    // ACTIVITY_HALF_LIFE_SECS since the last one, so we decay it here when it is read
+    // instead of storing it again every time.
+    fn activity_score(&self, now: i64) -> u64 {
+        let half_lives =
+            now.saturating_sub(self.activity_updated_at).max(0) / ACTIVITY_HALF_LIFE_SECS;
+        self.activity_score
+            .checked_shr(half_lives.min(u32::MAX as i64) as u32)
+            .unwrap_or(0)
+    }
+
+    // Checks a donation of `amount` lamports stays under max_per_tx and the hard cap.
+    fn check_donation_limits(&self, amount: u64) -> ProgramResult {
+        if let Some(max_per_tx) = self.max_per_tx {
+            if amount > max_per_tx {
+                msg!("A single donation can't exceed {} lamports", max_per_tx);
+                return Err(CrowdfundingError::DonationTooLarge.into());
+            }
+        }
+        let new_amount = self
+            .amount_donated
+            .checked_add(amount)
+            .ok_or(ProgramError::InvalidArgument)?;
+        if self.hard_cap != 0 && new_amount > self.hard_cap {
+            msg!("This donation would exceed the campaign hard cap");
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(())
+    }
+
+    // Counts a donation of `amount` lamports in its histogram bucket.
+    fn record_donation_size(&mut self, amount: u64) -> ProgramResult {
+        let bucket = DONATION_BUCKET_BOUNDS
+            .iter()
+            .take_while(|bound| amount >= **bound)
+            .count();
+        self.donation_histogram[bucket] = self.donation_histogram[bucket]
+            .checked_add(1)
+            .ok_or(ProgramError::InvalidArgument)?;
+        Ok(())
+    }
+
+    // Every instruction editing the campaign settings calls this first.
+    fn require_mutable(&self) -> ProgramResult {
+        if self.immutable {
+            msg!("This campaign is immutable");
+            return Err(CrowdfundingError::CampaignImmutable.into());
+        }
+        Ok(())
+    }
+
+    // The part of the donations the vesting schedule released at `now` that wasn't
+    // withdrawn yet. Without vesting everything is released.
+    fn vested_remaining(&self, now: i64) -> u64 {
+        if self.vesting_duration == 0 {
+            return u64::MAX;
+        }
+        let elapsed = now
+            .saturating_sub(self.created_at)
+            .clamp(0, self.vesting_duration);
+        let vested =
+            (self.amount_donated as u128 * elapsed as u128 / self.vesting_duration as u128) as u64;
+        vested.saturating_sub(self.total_withdrawn)
+    }
+
+    // Derives the status of the campaign at `now`. Every client should rely on this
+    // instead of recomputing it, so they all agree.
+    fn status(&self, now: i64) -> CampaignStatus {
+        if let Some(final_status) = self.final_status {
+            final_status
+        } else if !is_past_deadline(self.deadline, now) {
+            CampaignStatus::Active
+        } else if self.goal == 0 {
+            CampaignStatus::Ended
+        } else if self.amount_donated >= self.goal {
+            CampaignStatus::Succeeded
+        } else {
+            CampaignStatus::Failed
+        }
+    }
+
+    // Checks the settings the admin chose for the campaign, goals, hard cap, deadline and
+    // the blocked donors.
+    fn validate(&self, program_id: &Pubkey) -> ProgramResult {
+        // A stretch goal only makes sense on top of a real goal, and it has to be above it.
+        if self.stretch_goal != 0 && (self.goal == 0 || self.stretch_goal <= self.goal) {
+            msg!("Invalid instruction data, stretch_goal must be greater than goal");
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if self.goal > MAX_GOAL || self.stretch_goal > MAX_GOAL || self.hard_cap > MAX_GOAL {
+            msg!("Invalid instruction data, goals and hard_cap can't exceed MAX_GOAL");
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if self.deadline != 0 && self.deadline <= Clock::get()?.unix_timestamp {
+            msg!("Invalid instruction data, deadline must be in the future");
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        // A hard cap below one of the goals would make that goal unreachable.
+        if self.hard_cap != 0 && (self.hard_cap < self.goal || self.hard_cap < self.stretch_goal) {
+            msg!("Invalid instruction data, hard_cap can't be below the goals");
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if self.rent_buffer_bps > MAX_RENT_BUFFER_BPS {
+            msg!(
+                "Invalid instruction data, rent_buffer_bps can't exceed {}",
+                MAX_RENT_BUFFER_BPS
+            );
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        validate_text(&self.thank_you, MAX_THANK_YOU_LEN, "thank_you")?;
+
+        if !(0..=MAX_VESTING_DURATION_SECS).contains(&self.vesting_duration) {
+            msg!(
+                "Invalid instruction data, vesting_duration must be between 0 and {}",
+                MAX_VESTING_DURATION_SECS
+            );
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if !(0..=MAX_ENDING_SOON_WINDOW_SECS).contains(&self.ending_soon_window) {
+            msg!(
+                "Invalid instruction data, ending_soon_window must be between 0 and {}",
+                MAX_ENDING_SOON_WINDOW_SECS
+            );
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        if self.ending_soon_window != 0 && self.deadline == 0 {
+            msg!("Invalid instruction data, ending_soon_window needs a deadline");
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if self.referral_bps > MAX_REFERRAL_BPS {
+            msg!(
+                "Invalid instruction data, referral_bps can't exceed {}",
+                MAX_REFERRAL_BPS
+            );
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if self.validator_program == Some(*program_id) {
+            msg!("Invalid instruction data, the program can't validate its own campaigns");
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if self.max_per_tx == Some(0) {
+            msg!("Invalid instruction data, max_per_tx can't be zero");
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if self.blocked_donors.len() > MAX_BLOCKED_DONORS {
+            msg!(
+                "Invalid instruction data, can't block more than {} donors",
+                MAX_BLOCKED_DONORS
+            );
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        // The admin already signs for itself, as its own cosigner or guardian it would add nothing.
+        if self.cosigner == Some(self.admin) || self.guardian == Some(self.admin) {
+            msg!("Invalid instruction data, cosigner and guardian can't be the admin");
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(())
+    }
 }
 
 fn create_campaign(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    instruction_data: &[u8],
+    mut input_data: CampaignDetails,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
 
@@ -78,18 +735,19 @@ fn create_campaign(
     let creator_account = next_account_info(accounts_iter)?;
 
     // Now to allow transactions we want the creator account to sign the transaction.instruction_data
-    if !creator_account.is_signer {
-        msg!("creator_account must be a signer");
-        return Err(ProgramError::IncorrectProgramId);
-    }
+    require_signer(creator_account, "creator_account")?;
+    check_signer_account(program_id, writing_account, creator_account)?;
     // We want to write in this account so we want its owner by the program.
     if writing_account.owner != program_id {
         msg!("writing_account is'nt owned by program");
         return Err(ProgramError::IncorrectProgramId);
     }
-
-    let mut input_data = CampaignDetails::try_from_slice(&instruction_data)
-        .expect("Instruction data serialization didn't worked");
+    // We never write over an existing campaign, that would reset its books and drop the
+    // protections it was created with.
+    if writing_account.data.borrow().iter().any(|byte| *byte != 0) {
+        msg!("writing_account is already initialized");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
 
     // Now I want that for a campaign created the only admin should be the one who created it.
     if input_data.admin != *creator_account.key {
@@ -97,6 +755,26 @@ fn create_campaign(
         return Err(ProgramError::InvalidInstructionData);
     }
 
+    input_data.validate(program_id)?;
+
+    // Then we can set the initial amount donate to be zero.
+    input_data.amount_donated = 0;
+    input_data.total_withdrawn = 0;
+    input_data.ending_soon_notified = false;
+    input_data.final_status = None;
+    input_data.directory_index = None;
+    input_data.activity_score = 0;
+    input_data.activity_updated_at = 0;
+    input_data.created_at = Clock::get()?.unix_timestamp;
+    input_data.version = CAMPAIGN_VERSION;
+
+    // The account must be allocated to the exact size of the campaign. If it is smaller the rent
+    // below is computed for the wrong size, and if it is bigger the campaign can't be read back.
+    if writing_account.data_len() != input_data.space()? {
+        msg!("writing_account data length doesn't match the campaign size");
+        return Err(CrowdfundingError::AccountSizeMismatch.into());
+    }
+
     // get the minimum balance we need in our program account
     let rent_exemption = Rent::get()?.minimum_balance(writing_account.data_len());
 
@@ -105,9 +783,13 @@ fn create_campaign(
         msg!("The balance of writing_account must be more then rent_exemption");
         return Err(ProgramError::InsufficientFunds);
     }
-
-    // Then we can set the initial amount donate to be zero.
-    input_data.amount_donated = 0;
+    if **writing_account.lamports.borrow() < rent_exemption.saturating_add(MIN_INITIAL_FUNDING) {
+        msg!(
+            "writing_account must hold at least {} lamports above rent",
+            MIN_INITIAL_FUNDING
+        );
+        return Err(CrowdfundingError::InsufficientInitialFunding.into());
+    }
 
     // If everything went well, we write all the data into the writing_account
     input_data.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
@@ -120,62 +802,151 @@ struct WithdrawRequest {
     pub amount: u64,
 }
 
+// With `or_max` set, asking for more than the withdrawable balance withdraws the whole
+// withdrawable balance instead of failing. Returns the amount actually withdrawn.
 fn withdraw(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    instruction_data: &[u8],
-) -> ProgramResult {
+    input_data: WithdrawRequest,
+    or_max: bool,
+) -> Result<u64, ProgramError> {
     // create a new iteration on accounts
     let accounts_iter = &mut accounts.iter();
     let writing_account = next_account_info(accounts_iter)?;
     let admin_account = next_account_info(accounts_iter)?;
 
-    // We check if the writing account is owned by program.
-    if writing_account.owner != program_id {
-        msg!("writing_account isn't owned by program");
-        return Err(ProgramError::IncorrectProgramId);
-    }
-
-    // Admin account should be the signer in this transaction.
-    if !admin_account.is_signer {
-        msg!("admin should be signer");
-        return Err(ProgramError::IncorrectProgramId);
-    }
+    check_signer_account(program_id, writing_account, admin_account)?;
 
-    let mut campaign_data = CampaignDetails::try_from_slice(*writing_account.data.borrow())
-        .expect("Error deserializing data");
+    let mut campaign_data = CampaignDetails::load(program_id, writing_account)?;
 
-    // Then we check if the admin_account's public key is equal to
-    // the public key we have stored in our campaign_data.
-    if campaign_data.admin != *admin_account.key {
-        msg!("Only the account admin can withdraw");
-        return Err(ProgramError::InvalidAccountData);
+    // Then we check the admin_account signed and is the admin we have stored in our campaign_data.
+    require_role(admin_account, &campaign_data, Role::Admin)?;
+    require_cosigner(accounts, &campaign_data)?;
+    // It receives the lamports, without this the runtime rejects the credit with a vague error.
+    if !admin_account.is_writable {
+        msg!("admin_account should be writable");
+        return Err(CrowdfundingError::AccountNotWritable.into());
     }
 
-    // Here we make use of the struct we created.
-    // We will get the amount of lamports admin wants to withdraw
-    let input_data = WithdrawRequest::try_from_slice(&instruction_data)
-        .expect("Instruction data serialization didn't worked");
-
-    // we don't want the campaign to be deleted after a withdrawal, so we check the rent-exempt
-    let rent_exemption = Rent::get()?.minimum_balance(writing_account.data_len());
+    // we don't want the campaign to be deleted after a withdrawal, so we keep the rent-exempt
+    // balance and the campaign rent buffer out of reach
+    let withdrawable = campaign_data.withdrawable(writing_account)?;
+    // and with a vesting schedule only what was released so far can go
+    let vested = campaign_data.vested_remaining(Clock::get()?.unix_timestamp);
+    let available = withdrawable.min(vested);
 
     // We check if we have enough funds
-    if **writing_account.lamports.borrow() - rent_exemption < input_data.amount {
+    let amount = if input_data.amount <= available {
+        let dust = available - input_data.amount;
+        if campaign_data.sweep_dust && dust != 0 && dust < DUST_THRESHOLD {
+            msg!("Sweeping {} lamports of dust with the withdrawal", dust);
+            available
+        } else {
+            input_data.amount
+        }
+    } else if or_max {
+        msg!(
+            "Requested {} but only {} is withdrawable",
+            input_data.amount,
+            available
+        );
+        available
+    } else if input_data.amount > withdrawable {
         msg!("Insufficent balance");
         return Err(ProgramError::InsufficientFunds);
-    }
+    } else {
+        msg!("Only {} lamports are vested so far", vested);
+        return Err(CrowdfundingError::ExceedsVestedAmount.into());
+    };
+
+    run_validator(
+        accounts,
+        &campaign_data,
+        writing_account,
+        admin_account,
+        ValidatedOperation::Withdraw,
+        amount,
+    )?;
 
     // I everything went well, we transfere balance
-    **writing_account.try_borrow_mut_lamports()? -= input_data.amount;
-    **admin_account.try_borrow_mut_lamports()? += input_data.amount;
+    **writing_account.try_borrow_mut_lamports()? -= amount;
+    **admin_account.try_borrow_mut_lamports()? += amount;
+
+    campaign_data.total_withdrawn = campaign_data
+        .total_withdrawn
+        .checked_add(amount)
+        .ok_or(ProgramError::InvalidArgument)?;
+    campaign_data.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
+
+    // The amount actually withdrawn goes back as little-endian u64 return data.
+    set_return_data(&amount.to_le_bytes());
+
+    Ok(amount)
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct WithdrawWithNoteRequest {
+    pub amount: u64,
+    pub note: String,
+}
+
+// The note isn't stored, it is logged as CF:WITHDRAW_NOTE so donors can follow how the
+// funds are spent from the transaction history of the campaign.
+fn withdraw_with_note(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input_data: WithdrawWithNoteRequest,
+) -> ProgramResult {
+    if input_data.note.is_empty() {
+        msg!("Invalid instruction data, note can't be empty");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    validate_text(&input_data.note, MAX_WITHDRAW_NOTE_LEN, "note")?;
+
+    // With sweep_dust the withdrawal can be bigger than asked, we log what really left.
+    let withdrawn = withdraw(
+        program_id,
+        accounts,
+        WithdrawRequest {
+            amount: input_data.amount,
+        },
+        false,
+    )?;
+    msg!("CF:WITHDRAW_NOTE {} {}", withdrawn, input_data.note);
+
+    Ok(())
+}
+
+// A donator program account is an empty program-owned account holding only the lamports to
+// donate. The program owns other accounts with lamports too, vouchers, directory pages and
+// campaigns, and those all hold data, so we only sweep accounts without any.
+fn check_donator_program_account(
+    program_id: &Pubkey,
+    writing_account: &AccountInfo,
+    donator_program_account: &AccountInfo,
+) -> ProgramResult {
+    if donator_program_account.owner != program_id {
+        msg!("donator_program_account isn't owned by program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if donator_program_account.key == writing_account.key {
+        msg!("donator_program_account can't be the writing_account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if !donator_program_account.data_is_empty() {
+        msg!("donator_program_account must not hold any data");
+        return Err(ProgramError::InvalidAccountData);
+    }
     Ok(())
 }
 
+// With `create_with` set, the donator program account doesn't exist yet: the donator creates
+// it with that many lamports right here, and the system program follows the donator in the
+// accounts. The rest of the donation works the same as with an account made by the front-end.
 fn donate(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    _instruction_data: &[u8],
+    create_with: Option<u64>,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     let writing_account = next_account_info(accounts_iter)?;
@@ -183,31 +954,1598 @@ fn donate(
     let donator_program_account = next_account_info(accounts_iter)?;
     let donator = next_account_info(accounts_iter)?;
 
+    if let Some(lamports) = create_with {
+        let system_program_account = next_account_info(accounts_iter)?;
+        require_signer(donator, "donator")?;
+        require_signer(donator_program_account, "donator_program_account")?;
+        // Checked before any lamports move, accept_donation would only catch it afterwards.
+        if lamports == 0 {
+            msg!("A donation must be greater than zero");
+            return Err(CrowdfundingError::InvalidAmount.into());
+        }
+        invoke(
+            &system_instruction::create_account(
+                donator.key,
+                donator_program_account.key,
+                lamports,
+                0,
+                program_id,
+            ),
+            &[
+                donator.clone(),
+                donator_program_account.clone(),
+                system_program_account.clone(),
+            ],
+        )?;
+    }
+
+    check_donator_program_account(program_id, writing_account, donator_program_account)?;
+    require_signer(donator, "donator")?;
+    check_signer_account(program_id, writing_account, donator)?;
+
+    let mut campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+    // we increase the total amount donated by the amount in our donator program account
+    let donation = donator_program_account.lamports();
+    campaign_data.accept_donation(donator.key, donation)?;
+    run_validator(
+        accounts,
+        &campaign_data,
+        writing_account,
+        donator,
+        ValidatedOperation::Donate,
+        donation,
+    )?;
+
+    // we do the actual transaction
+    **writing_account.try_borrow_mut_lamports()? += donation;
+    **donator_program_account.try_borrow_mut_lamports()? = 0;
+
+    // we will write the new updated campaign_data to the writing_account
+    campaign_data.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
+
+    DonationTracking::find(program_id, writing_account, accounts).record(
+        program_id,
+        writing_account,
+        donator,
+        donation,
+    )?;
+
+    // We hand the new total back to the client as little-endian u64 return data,
+    // so it doesn't need to read the account again after donating.
+    set_return_data(&campaign_data.amount_donated.to_le_bytes());
+
+    Ok(())
+}
+
+// The referrer gets its share of the donator program account straight away, only the rest
+// counts as donated to the campaign. The campaign, its admin and the donator can't refer
+// donations. The limits of the campaign apply to the whole donation, referral included.
+fn donate_with_referrer(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    let donator_program_account = next_account_info(accounts_iter)?;
+    let donator = next_account_info(accounts_iter)?;
+    let referrer = next_account_info(accounts_iter)?;
+
+    check_donator_program_account(program_id, writing_account, donator_program_account)?;
+    require_signer(donator, "donator")?;
+    check_signer_account(program_id, writing_account, donator)?;
+
+    let mut campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+    if referrer.key == writing_account.key
+        || referrer.key == donator_program_account.key
+        || *referrer.key == campaign_data.admin
+    {
+        msg!("The campaign and its admin can't be referrers");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if referrer.key == donator.key {
+        msg!("The donator can't refer its own donation");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if !referrer.is_writable {
+        msg!("referrer should be writable");
+        return Err(CrowdfundingError::AccountNotWritable.into());
+    }
+
+    let donation = donator_program_account.lamports();
+    let referral =
+        (donation as u128 * campaign_data.referral_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    let campaign_share = donation - referral;
+
+    campaign_data.check_donation_limits(donation)?;
+    campaign_data.accept_donation(donator.key, campaign_share)?;
+    run_validator(
+        accounts,
+        &campaign_data,
+        writing_account,
+        donator,
+        ValidatedOperation::Donate,
+        campaign_share,
+    )?;
+
+    **writing_account.try_borrow_mut_lamports()? += campaign_share;
+    **referrer.try_borrow_mut_lamports()? += referral;
+    **donator_program_account.try_borrow_mut_lamports()? = 0;
+
+    campaign_data.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
+    msg!("CF:REFERRAL {} {}", referrer.key, referral);
+
+    DonationTracking::find(program_id, writing_account, accounts).record(
+        program_id,
+        writing_account,
+        donator,
+        campaign_share,
+    )?;
+
+    set_return_data(&campaign_data.amount_donated.to_le_bytes());
+
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct CreateAndDonateRequest {
+    // The lamports the donator moves into the new donator program account, all of them are donated.
+    pub amount: u64,
+}
+
+// Tells if a donation moved the total from below `target` to at least `target`.
+// A target of 0 means the tier isn't set, so it is never crossed.
+fn crossed(previous_amount: u64, new_amount: u64, target: u64) -> bool {
+    target != 0 && previous_amount < target && new_amount >= target
+}
+
+// This is how campaigns were stored before we added the version byte and the goals.
+// We only keep it around to read those accounts in `migrate_v0_to_v1`.
+#[derive(BorshDeserialize, Debug)]
+struct CampaignDetailsV0 {
+    pub admin: Pubkey,
+    pub name: String,
+    pub description: String,
+    pub image_link: String,
+    pub amount_donated: u64,
+}
+
+fn migrate_v0_to_v1(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    // The admin signs the migration and pays for the extra rent of the bigger account.
+    let admin_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
     if writing_account.owner != program_id {
         msg!("writing_account isn't owned by program");
         return Err(ProgramError::IncorrectProgramId);
     }
-    if donator_program_account.owner != program_id {
-        msg!("donator_program_account isn't owned by program");
-        return Err(ProgramError::IncorrectProgramId);
+    check_signer_account(program_id, writing_account, admin_account)?;
+
+    let migrated = {
+        let data = writing_account.data.borrow();
+
+        // If the account already reads as a current campaign it has been migrated before,
+        // running the migration again would corrupt it.
+        if let Ok(current) = CampaignDetails::try_from_slice(&data) {
+            if current.version == CAMPAIGN_VERSION {
+                msg!("writing_account is already migrated");
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+        }
+
+        // The old layout must consume the whole account, otherwise it isn't a v0 campaign.
+        let legacy = CampaignDetailsV0::try_from_slice(&data).map_err(|_| {
+            msg!("writing_account doesn't hold a v0 campaign");
+            ProgramError::InvalidAccountData
+        })?;
+
+        // The v0 admin is the authority of the account until it holds a current campaign.
+        require_authority(admin_account, &legacy.admin)?;
+
+        // v0 didn't count withdrawals, but donated lamports missing from the balance above rent
+        // can only have been withdrawn. Counting them keeps Reconcile from flagging the campaign.
+        let held = writing_account
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(writing_account.data_len()));
+        let total_withdrawn = legacy.amount_donated.saturating_sub(held);
+
+        // The fields that didn't exist in v0 get their "not set" value.
+        CampaignDetails {
+            version: CAMPAIGN_VERSION,
+            admin: legacy.admin,
+            name: legacy.name,
+            description: legacy.description,
+            image_link: legacy.image_link,
+            amount_donated: legacy.amount_donated,
+            goal: 0,
+            stretch_goal: 0,
+            hard_cap: 0,
+            deadline: 0,
+            immutable: false,
+            blocked_donors: Vec::new(),
+            rent_buffer_bps: 0,
+            thank_you: String::new(),
+            donation_histogram: [0; DONATION_BUCKETS],
+            max_per_tx: None,
+            // We don't know when v0 campaigns were created, they don't vest anyway.
+            created_at: 0,
+            vesting_duration: 0,
+            total_withdrawn,
+            validator_program: None,
+            ending_soon_window: 0,
+            ending_soon_notified: false,
+            final_status: None,
+            metadata_hash: [0; 32],
+            sweep_dust: false,
+            directory_index: None,
+            referral_bps: 0,
+            activity_score: 0,
+            activity_updated_at: 0,
+            cosigner: None,
+            guardian: None,
+        }
+    };
+
+    resize_account(
+        writing_account,
+        admin_account,
+        system_program,
+        migrated.space()?,
+    )?;
+    migrated.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct RaiseHardCapRequest {
+    pub new_cap: u64,
+}
+
+fn raise_hard_cap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input_data: RaiseHardCapRequest,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    let admin_account = next_account_info(accounts_iter)?;
+
+    check_signer_account(program_id, writing_account, admin_account)?;
+
+    let mut campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+    require_role(admin_account, &campaign_data, Role::Admin)?;
+    campaign_data.require_mutable()?;
+
+    // An uncapped campaign has nothing to raise.
+    if campaign_data.hard_cap == 0 {
+        msg!("This campaign has no hard cap");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // The cap can only go up, and never below what was already donated.
+    if input_data.new_cap <= campaign_data.hard_cap
+        || input_data.new_cap < campaign_data.amount_donated
+    {
+        msg!("new_cap must be greater than the current hard cap");
+        return Err(ProgramError::InvalidInstructionData);
     }
-    if !donator.is_signer {
-        msg!("donator should be signer");
+    if input_data.new_cap > MAX_GOAL {
+        msg!("new_cap can't exceed MAX_GOAL");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    campaign_data.hard_cap = input_data.new_cap;
+    campaign_data.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct ForkCampaignRequest {
+    // The lamports moved from the parent campaign to the child.
+    pub amount: u64,
+    // The child campaign, written like in create_campaign. Its admin must be the parent's admin.
+    pub child: CampaignDetails,
+}
+
+fn fork_campaign(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input_data: ForkCampaignRequest,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    // The child account is created in the front-end like a regular writing account.
+    let child_account = next_account_info(accounts_iter)?;
+    let admin_account = next_account_info(accounts_iter)?;
+
+    if child_account.owner != program_id {
+        msg!("child_account isn't owned by program");
         return Err(ProgramError::IncorrectProgramId);
     }
+    if child_account.key == writing_account.key {
+        msg!("child_account must be a different account");
+        return Err(ProgramError::InvalidArgument);
+    }
+    check_signer_account(program_id, writing_account, admin_account)?;
+    check_signer_account(program_id, child_account, admin_account)?;
 
-    let mut campaign_data = CampaignDetails::try_from_slice(*writing_account.data.borrow())
-        .expect("Error deserializing data");
+    let mut campaign_data = CampaignDetails::load(program_id, writing_account)?;
 
-    // we increase the total amount donated by the amount in our donator program account
-    campaign_data.amount_donated += **donator_program_account.lamports.borrow();
+    require_role(admin_account, &campaign_data, Role::Admin)?;
+    // Forking moves the donations into a campaign with new terms, an immutable campaign
+    // keeps its terms for everything it received.
+    campaign_data.require_mutable()?;
 
-    // we do the actual transaction
-    **writing_account.try_borrow_mut_lamports()? += **donator_program_account.lamports.borrow();
-    **donator_program_account.try_borrow_mut_lamports()? = 0;
+    // We never write over an existing campaign.
+    if child_account.data.borrow().iter().any(|byte| *byte != 0) {
+        msg!("child_account is already initialized");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
 
-    // we will write the new updated campaign_data to the writing_account
+    let mut child_data = input_data.child;
+    if child_data.admin != campaign_data.admin {
+        msg!("Invalid instruction data, the child admin must be the parent admin");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    child_data.validate(program_id)?;
+    child_data.version = CAMPAIGN_VERSION;
+    child_data.amount_donated = input_data.amount;
+    child_data.total_withdrawn = 0;
+    child_data.ending_soon_notified = false;
+    child_data.final_status = None;
+    child_data.directory_index = None;
+    child_data.activity_score = 0;
+    child_data.activity_updated_at = 0;
+    // The child keeps vesting on the parent schedule, otherwise forking into a child without
+    // vesting would release the forked lamports right away.
+    child_data.vesting_duration = campaign_data.vesting_duration;
+    child_data.created_at = campaign_data.created_at;
+    // The same goes for the validator program and the cosigner, they must approve the
+    // withdrawals from the child.
+    child_data.validator_program = campaign_data.validator_program;
+    child_data.cosigner = campaign_data.cosigner;
+
+    if child_account.data_len() != child_data.space()? {
+        msg!("child_account data length doesn't match the campaign size");
+        return Err(CrowdfundingError::AccountSizeMismatch.into());
+    }
+
+    // The front-end pays the child rent, the moved lamports all count as donations.
+    let rent = Rent::get()?;
+    if **child_account.lamports.borrow() < rent.minimum_balance(child_account.data_len()) {
+        msg!("The balance of child_account must be more then rent_exemption");
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    if input_data.amount == 0 {
+        msg!("The forked amount must be greater than zero");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // The moved lamports leave the parent's donated total, and the parent keeps its rent
+    // and rent buffer.
+    campaign_data.amount_donated = campaign_data
+        .amount_donated
+        .checked_sub(input_data.amount)
+        .ok_or_else(|| {
+            msg!("Can't fork more than the amount donated");
+            ProgramError::InsufficientFunds
+        })?;
+    if campaign_data.withdrawable(writing_account)? < input_data.amount {
+        msg!("Insufficent balance");
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    **writing_account.try_borrow_mut_lamports()? -= input_data.amount;
+    **child_account.try_borrow_mut_lamports()? += input_data.amount;
+
+    campaign_data.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
+    child_data.serialize(&mut &mut child_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// The latest donors of a campaign, newest first. Only the first `count` entries are used.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
+struct RecentDonors {
+    pub count: u8,
+    pub donors: [Pubkey; RECENT_DONORS_LEN],
+}
+
+impl RecentDonors {
+    const SPACE: usize = 1 + PUBKEY_BYTES * RECENT_DONORS_LEN;
+
+    // Puts `donor` in front of the list, the oldest one falls off once the list is full.
+    fn push(&mut self, donor: Pubkey) {
+        self.donors.copy_within(0..RECENT_DONORS_LEN - 1, 1);
+        self.donors[0] = donor;
+        self.count = (self.count as usize + 1).min(RECENT_DONORS_LEN) as u8;
+    }
+
+    fn list(&self) -> &[Pubkey] {
+        &self.donors[..self.count as usize]
+    }
+}
+
+// Checks `recent_donors_account` is the recent donors PDA of the campaign and returns its bump.
+fn recent_donors_bump(
+    program_id: &Pubkey,
+    writing_account: &AccountInfo,
+    recent_donors_account: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let (expected_key, bump) = Pubkey::find_program_address(
+        &[RECENT_DONORS_SEED, writing_account.key.as_ref()],
+        program_id,
+    );
+    if expected_key != *recent_donors_account.key {
+        msg!("recent_donors_account isn't the recent donors PDA of this campaign");
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump)
+}
+
+fn record_recent_donor<'a>(
+    program_id: &Pubkey,
+    writing_account: &AccountInfo<'a>,
+    recent_donors_account: &AccountInfo<'a>,
+    donator: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let bump = recent_donors_bump(program_id, writing_account, recent_donors_account)?;
+
+    if recent_donors_account.data_is_empty() {
+        create_pda_account(
+            donator,
+            recent_donors_account,
+            system_program,
+            program_id,
+            RecentDonors::SPACE,
+            &[RECENT_DONORS_SEED, writing_account.key.as_ref(), &[bump]],
+        )?;
+    } else if recent_donors_account.owner != program_id {
+        msg!("recent_donors_account isn't owned by program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // A freshly created account is all zeros, which reads as an empty list.
+    let mut recent = RecentDonors::try_from_slice(&recent_donors_account.data.borrow())?;
+    recent.push(*donator.key);
+    recent.serialize(&mut &mut recent_donors_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn recent_donors(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    let recent_donors_account = next_account_info(accounts_iter)?;
+
+    CampaignDetails::load(program_id, writing_account)?;
+    recent_donors_bump(program_id, writing_account, recent_donors_account)?;
+
+    // Nobody donated with the list enabled yet, so the PDA doesn't exist.
+    let recent = if recent_donors_account.data_is_empty() {
+        RecentDonors::default()
+    } else {
+        if recent_donors_account.owner != program_id {
+            msg!("recent_donors_account isn't owned by program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        RecentDonors::try_from_slice(&recent_donors_account.data.borrow())?
+    };
+
+    set_return_data(&recent.list().to_vec().try_to_vec()?);
+
+    Ok(())
+}
+
+// The lamports donated to a campaign during one epoch.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, Copy)]
+struct EpochDonation {
+    pub epoch: u64,
+    pub amount: u64,
+}
+
+// Donations of the latest epochs, as a ring buffer indexed by `epoch % EPOCH_DONATIONS_LEN`.
+// A slot holding another epoch than the one asked for is stale.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
+struct EpochDonations {
+    pub epochs: [EpochDonation; EPOCH_DONATIONS_LEN],
+}
+
+impl EpochDonations {
+    const SPACE: usize = 16 * EPOCH_DONATIONS_LEN;
+
+    // Adds `amount` to the slot of `epoch`, dropping what an older epoch left in it.
+    fn add(&mut self, epoch: u64, amount: u64) -> ProgramResult {
+        let slot = &mut self.epochs[(epoch % EPOCH_DONATIONS_LEN as u64) as usize];
+        if slot.epoch != epoch {
+            *slot = EpochDonation { epoch, amount: 0 };
+        }
+        slot.amount = slot
+            .amount
+            .checked_add(amount)
+            .ok_or(ProgramError::InvalidArgument)?;
+        Ok(())
+    }
+}
+
+fn record_epoch_donation<'a>(
+    program_id: &Pubkey,
+    writing_account: &AccountInfo<'a>,
+    epoch_donations_account: &AccountInfo<'a>,
+    donator: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    let (expected_key, bump) = Pubkey::find_program_address(
+        &[EPOCH_DONATIONS_SEED, writing_account.key.as_ref()],
+        program_id,
+    );
+    if expected_key != *epoch_donations_account.key {
+        msg!("epoch_donations_account isn't the per-epoch donations PDA of this campaign");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if epoch_donations_account.data_is_empty() {
+        create_pda_account(
+            donator,
+            epoch_donations_account,
+            system_program,
+            program_id,
+            EpochDonations::SPACE,
+            &[EPOCH_DONATIONS_SEED, writing_account.key.as_ref(), &[bump]],
+        )?;
+    } else if epoch_donations_account.owner != program_id {
+        msg!("epoch_donations_account isn't owned by program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // A freshly created account is all zeros, every slot reads as an empty epoch 0.
+    let mut epochs = EpochDonations::try_from_slice(&epoch_donations_account.data.borrow())?;
+    epochs.add(Clock::get()?.epoch, amount)?;
+    epochs.serialize(&mut &mut epoch_donations_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// The optional accounts tracking donations. Clients that want the "recent supporters" list
+// or the per-epoch report pass the matching PDA with the system program, and the donator pays
+// for a PDA the first time. We look them up by address among all the accounts, so they can
+// come in any order, next to other optional accounts like the validator program.
+struct DonationTracking<'a, 'b> {
+    recent_donors: Option<&'a AccountInfo<'b>>,
+    epoch_donations: Option<&'a AccountInfo<'b>>,
+    system_program: Option<&'a AccountInfo<'b>>,
+}
+
+impl<'a, 'b> DonationTracking<'a, 'b> {
+    fn find(
+        program_id: &Pubkey,
+        writing_account: &AccountInfo,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Self {
+        let find = |key: &Pubkey| accounts.iter().find(|account| account.key == key);
+        let (recent_donors_key, _) = Pubkey::find_program_address(
+            &[RECENT_DONORS_SEED, writing_account.key.as_ref()],
+            program_id,
+        );
+        let (epoch_donations_key, _) = Pubkey::find_program_address(
+            &[EPOCH_DONATIONS_SEED, writing_account.key.as_ref()],
+            program_id,
+        );
+        Self {
+            recent_donors: find(&recent_donors_key),
+            epoch_donations: find(&epoch_donations_key),
+            system_program: find(&system_program::id()),
+        }
+    }
+
+    // Records a donation of `amount` lamports from `donator` in the PDAs the client passed.
+    fn record(
+        &self,
+        program_id: &Pubkey,
+        writing_account: &AccountInfo<'b>,
+        donator: &AccountInfo<'b>,
+        amount: u64,
+    ) -> ProgramResult {
+        if self.recent_donors.is_none() && self.epoch_donations.is_none() {
+            return Ok(());
+        }
+        let system_program = self.system_program.ok_or_else(|| {
+            msg!("The system program must be passed with the donation tracking accounts");
+            ProgramError::NotEnoughAccountKeys
+        })?;
+
+        if let Some(recent_donors_account) = self.recent_donors {
+            record_recent_donor(
+                program_id,
+                writing_account,
+                recent_donors_account,
+                donator,
+                system_program,
+            )?;
+        }
+        if let Some(epoch_donations_account) = self.epoch_donations {
+            record_epoch_donation(
+                program_id,
+                writing_account,
+                epoch_donations_account,
+                donator,
+                system_program,
+                amount,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Resizes a program-owned account to `new_len` bytes. When it grows, `payer` pays the extra
+// rent first, so the reserved rent never eats into the donated lamports. When it shrinks,
+// the rent that isn't needed anymore goes back to `payer`.
+// The runtime only lets an account grow by MAX_PERMITTED_DATA_INCREASE bytes per instruction,
+// we reject bigger growth with a clear error instead of letting the runtime fail the call.
+fn resize_account<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    new_len: usize,
+) -> ProgramResult {
+    let old_len = account.data_len();
+    if new_len.saturating_sub(old_len) > MAX_PERMITTED_DATA_INCREASE {
+        msg!(
+            "Can't grow an account by more than {} bytes in one instruction",
+            MAX_PERMITTED_DATA_INCREASE
+        );
+        return Err(CrowdfundingError::ReallocTooLarge.into());
+    }
+
+    let rent = Rent::get()?;
+    let extra_rent = rent
+        .minimum_balance(new_len)
+        .saturating_sub(rent.minimum_balance(old_len));
+    if extra_rent > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, extra_rent),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    let freed_rent = rent
+        .minimum_balance(old_len)
+        .saturating_sub(rent.minimum_balance(new_len));
+    if freed_rent > 0 {
+        **account.try_borrow_mut_lamports()? -= freed_rent;
+        **payer.try_borrow_mut_lamports()? += freed_rent;
+    }
+
+    account.realloc(new_len, false)
+}
+
+// Creates a program-owned PDA of `space` bytes, rent exempt and paid by `payer`.
+// PDA addresses are easy to predict, and anyone can send lamports to one before we create it.
+// create_account refuses an address that holds lamports, so for those we only top up the
+// rent and allocate and assign the account ourselves, otherwise a single lamport would block
+// the PDA for good.
+fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    pda_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    program_id: &Pubkey,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let lamports = Rent::get()?.minimum_balance(space);
+    let current_lamports = pda_account.lamports();
+    if current_lamports == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                pda_account.key,
+                lamports,
+                space as u64,
+                program_id,
+            ),
+            &[payer.clone(), pda_account.clone(), system_program.clone()],
+            &[signer_seeds],
+        );
+    }
+
+    let top_up = lamports.saturating_sub(current_lamports);
+    if top_up > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, pda_account.key, top_up),
+            &[payer.clone(), pda_account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(pda_account.key, space as u64),
+        &[pda_account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(pda_account.key, program_id),
+        &[pda_account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )
+}
+
+// The status of a campaign as returned by the Status instruction.
+// The codes are part of the client interface, never renumber them.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CampaignStatus {
+    // Still accepting donations.
+    Active = 0,
+    // Past its deadline, without a goal to succeed or fail.
+    Ended = 1,
+    // Past its deadline with the goal reached.
+    Succeeded = 2,
+    // Past its deadline without reaching the goal.
+    Failed = 3,
+}
+
+fn status(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+
+    let campaign_data = CampaignDetails::load(program_id, writing_account)?;
+    let status = campaign_data.status(Clock::get()?.unix_timestamp);
+
+    set_return_data(&[status as u8]);
+
+    Ok(())
+}
+
+// Moves the deadline to now, so donations stop and the outcome follows from what was raised.
+// The guardian of the campaign can do it too.
+fn end_now(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    let admin_account = next_account_info(accounts_iter)?;
+
+    check_signer_account(program_id, writing_account, admin_account)?;
+
+    let mut campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+    require_any_role(
+        admin_account,
+        &campaign_data,
+        &[Role::Admin, Role::Guardian],
+    )?;
+    campaign_data.require_mutable()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    if campaign_data.final_status.is_some() || is_past_deadline(campaign_data.deadline, now) {
+        msg!("This campaign has ended");
+        return Err(CrowdfundingError::CampaignEnded.into());
+    }
+
+    // A donation is still accepted CLOCK_TOLERANCE_SECS after the deadline, so we put the
+    // deadline that far in the past for the campaign to end right away.
+    campaign_data.deadline = now.saturating_sub(CLOCK_TOLERANCE_SECS + 1);
     campaign_data.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
+    msg!("CF:ENDED_EARLY {}", now);
+
+    Ok(())
+}
+
+// Clients sort campaigns by this score to show the most active ones first.
+fn activity_score(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+
+    let campaign_data = CampaignDetails::load(program_id, writing_account)?;
+    let score = campaign_data.activity_score(Clock::get()?.unix_timestamp);
+
+    set_return_data(&score.to_le_bytes());
+
+    Ok(())
+}
+
+// A campaign at or over its goal has a shortfall of 0, one without a goal returns
+// NO_GOAL_SHORTFALL.
+fn shortfall(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+
+    let campaign_data = CampaignDetails::load(program_id, writing_account)?;
+    let shortfall = if campaign_data.goal == 0 {
+        NO_GOAL_SHORTFALL
+    } else {
+        campaign_data
+            .goal
+            .saturating_sub(campaign_data.amount_donated)
+    };
+
+    set_return_data(&shortfall.to_le_bytes());
+
+    Ok(())
+}
+
+// A campaign should always hold at least its rent plus whatever was donated and not withdrawn.
+// More is fine (initial funding, direct transfers) and is returned as the surplus. Less means
+// lamports left the account without going through withdraw, which is a bug or an exploit.
+fn reconcile(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+
+    let campaign_data = CampaignDetails::load(program_id, writing_account)?;
+    let expected = campaign_data
+        .amount_donated
+        .saturating_sub(campaign_data.total_withdrawn)
+        .saturating_add(Rent::get()?.minimum_balance(writing_account.data_len()));
+    let balance = writing_account.lamports();
+
+    if balance < expected {
+        msg!(
+            "CF:BALANCE_SHORTFALL expected {} lamports, found {}",
+            expected,
+            balance
+        );
+        return Err(CrowdfundingError::BalanceShortfall.into());
+    }
+
+    set_return_data(&(balance - expected).to_le_bytes());
+
+    Ok(())
+}
+
+// Anyone can prune, the lamports always go back to the campaign admin.
+fn prune(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    // The admin doesn't need to sign, it only receives the lamports of the closed campaign.
+    let admin_account = next_account_info(accounts_iter)?;
+
+    let campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+    if campaign_data.admin != *admin_account.key {
+        msg!("admin_account isn't the campaign admin");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // Only campaigns that ended without a single donation are abandoned. A campaign
+    // without deadline never ends, so it can't be pruned either.
+    if campaign_data.deadline == 0
+        || !is_past_deadline(campaign_data.deadline, Clock::get()?.unix_timestamp)
+    {
+        msg!("This campaign hasn't ended");
+        return Err(CrowdfundingError::CampaignNotPrunable.into());
+    }
+    if campaign_data.amount_donated != 0 {
+        msg!("This campaign received donations");
+        return Err(CrowdfundingError::CampaignNotPrunable.into());
+    }
+    // A finalized campaign keeps its recorded outcome, so it is never closed.
+    if campaign_data.final_status.is_some() {
+        msg!("This campaign is finalized");
+        return Err(CrowdfundingError::CampaignNotPrunable.into());
+    }
+
+    // We hand all the lamports back to the admin, wipe the data and give the account
+    // back to the system program, so nothing can read it as a campaign anymore.
+    let lamports = writing_account.lamports();
+    **writing_account.try_borrow_mut_lamports()? -= lamports;
+    **admin_account.try_borrow_mut_lamports()? += lamports;
+    writing_account.data.borrow_mut().fill(0);
+    writing_account.realloc(0, false)?;
+    writing_account.assign(&system_program::id());
+
+    Ok(())
+}
+
+// Every account after the keeper and the system program is a campaign to finalize, so a
+// keeper can resolve many campaigns in one transaction. Campaigns finalized by an earlier
+// batch are skipped, so batches can overlap.
+fn finalize_expired(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    // The recorded status makes every campaign one byte bigger, the keeper pays for that rent.
+    let keeper = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let campaigns = accounts_iter.as_slice();
+
+    if campaigns.len() > MAX_FINALIZE_BATCH {
+        msg!(
+            "Can't finalize more than {} campaigns at once",
+            MAX_FINALIZE_BATCH
+        );
+        return Err(ProgramError::InvalidArgument);
+    }
+    require_signer(keeper, "keeper")?;
+
+    let now = Clock::get()?.unix_timestamp;
+    for writing_account in campaigns {
+        check_signer_account(program_id, writing_account, keeper)?;
+        let mut campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+        if campaign_data.final_status.is_some() {
+            msg!("Campaign {} is already finalized", writing_account.key);
+            continue;
+        }
+        if !is_past_deadline(campaign_data.deadline, now) {
+            msg!("Campaign {} hasn't ended", writing_account.key);
+            return Err(CrowdfundingError::CampaignNotExpired.into());
+        }
+
+        let final_status = campaign_data.status(now);
+        campaign_data.final_status = Some(final_status);
+        resize_account(
+            writing_account,
+            keeper,
+            system_program,
+            campaign_data.space()?,
+        )?;
+        campaign_data.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
+        msg!(
+            "CF:FINALIZED {} {}",
+            writing_account.key,
+            final_status as u8
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct ThankYouRequest {
+    pub thank_you: String,
+}
+
+fn set_thank_you(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input_data: ThankYouRequest,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    // The admin pays for the account growing with a longer note.
+    let admin_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    check_signer_account(program_id, writing_account, admin_account)?;
+
+    let mut campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+    require_role(admin_account, &campaign_data, Role::Admin)?;
+    campaign_data.require_mutable()?;
+
+    validate_text(&input_data.thank_you, MAX_THANK_YOU_LEN, "thank_you")?;
+    campaign_data.thank_you = input_data.thank_you;
+
+    resize_account(
+        writing_account,
+        admin_account,
+        system_program,
+        campaign_data.space()?,
+    )?;
+    campaign_data.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct MetadataHashRequest {
+    pub metadata_hash: [u8; 32],
+}
+
+fn set_metadata_hash(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input_data: MetadataHashRequest,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    let admin_account = next_account_info(accounts_iter)?;
+
+    check_signer_account(program_id, writing_account, admin_account)?;
+
+    let mut campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+    require_role(admin_account, &campaign_data, Role::Admin)?;
+    campaign_data.require_mutable()?;
+
+    // An all zeros hash would silently turn the check off for clients.
+    if input_data.metadata_hash == [0; 32] {
+        msg!("Invalid instruction data, metadata_hash can't be all zeros");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    campaign_data.metadata_hash = input_data.metadata_hash;
+
+    // The hash has a fixed size, so the account keeps its size.
+    campaign_data.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn get_thank_you(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+
+    let campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+    set_return_data(campaign_data.thank_you.as_bytes());
+
+    Ok(())
+}
+
+fn donation_histogram(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+
+    let campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+    set_return_data(&campaign_data.donation_histogram.try_to_vec()?);
+
+    Ok(())
+}
+
+// A prepaid donation sitting in its own PDA until a donor redeems it.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct Voucher {
+    pub campaign: Pubkey,
+    // The admin who funded the voucher, it can still close the voucher once the campaign is
+    // closed.
+    pub admin: Pubkey,
+    // The lamports held by the voucher on top of its rent.
+    pub amount: u64,
+    pub redeemed: bool,
+}
+
+impl Voucher {
+    const SPACE: usize = PUBKEY_BYTES + PUBKEY_BYTES + 8 + 1;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct CreateVoucherRequest {
+    // Picked by the admin, it tells the vouchers of a campaign apart.
+    pub voucher_id: u64,
+    pub amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct VoucherRequest {
+    pub voucher_id: u64,
+}
+
+// Checks `voucher_account` is the PDA of voucher `voucher_id` of the campaign and returns its bump.
+fn voucher_bump(
+    program_id: &Pubkey,
+    writing_account: &AccountInfo,
+    voucher_account: &AccountInfo,
+    voucher_id: u64,
+) -> Result<u8, ProgramError> {
+    let (expected_key, bump) = Pubkey::find_program_address(
+        &[
+            VOUCHER_SEED,
+            writing_account.key.as_ref(),
+            &voucher_id.to_le_bytes(),
+        ],
+        program_id,
+    );
+    if expected_key != *voucher_account.key {
+        msg!("voucher_account isn't the PDA of voucher {}", voucher_id);
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump)
+}
+
+fn create_voucher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input_data: CreateVoucherRequest,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    // The admin pays for the voucher, its rent and the amount it holds.
+    let admin_account = next_account_info(accounts_iter)?;
+    let voucher_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    check_signer_account(program_id, writing_account, admin_account)?;
+
+    let campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+    require_role(admin_account, &campaign_data, Role::Admin)?;
+
+    if input_data.amount == 0 {
+        msg!("A voucher must hold more than zero lamports");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let bump = voucher_bump(
+        program_id,
+        writing_account,
+        voucher_account,
+        input_data.voucher_id,
+    )?;
+    // A redeemed voucher stays around, so a voucher id can never be funded twice.
+    if !voucher_account.data_is_empty() {
+        msg!("Voucher {} already exists", input_data.voucher_id);
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    create_pda_account(
+        admin_account,
+        voucher_account,
+        system_program,
+        program_id,
+        Voucher::SPACE,
+        &[
+            VOUCHER_SEED,
+            writing_account.key.as_ref(),
+            &input_data.voucher_id.to_le_bytes(),
+            &[bump],
+        ],
+    )?;
+    invoke(
+        &system_instruction::transfer(admin_account.key, voucher_account.key, input_data.amount),
+        &[
+            admin_account.clone(),
+            voucher_account.clone(),
+            system_program.clone(),
+        ],
+    )?;
+
+    let voucher = Voucher {
+        campaign: *writing_account.key,
+        admin: *admin_account.key,
+        amount: input_data.amount,
+        redeemed: false,
+    };
+    voucher.serialize(&mut &mut voucher_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn redeem_voucher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input_data: VoucherRequest,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    let voucher_account = next_account_info(accounts_iter)?;
+    let donator = next_account_info(accounts_iter)?;
+
+    require_signer(donator, "donator")?;
+    check_signer_account(program_id, writing_account, donator)?;
+
+    let mut campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+    voucher_bump(
+        program_id,
+        writing_account,
+        voucher_account,
+        input_data.voucher_id,
+    )?;
+    if voucher_account.owner != program_id {
+        msg!("voucher_account isn't owned by program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut voucher = Voucher::try_from_slice(&voucher_account.data.borrow())?;
+    if voucher.campaign != *writing_account.key {
+        msg!("This voucher belongs to another campaign");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if voucher.redeemed {
+        msg!("Voucher {} was already redeemed", input_data.voucher_id);
+        return Err(CrowdfundingError::VoucherAlreadyRedeemed.into());
+    }
+
+    campaign_data.accept_donation(donator.key, voucher.amount)?;
+    run_validator(
+        accounts,
+        &campaign_data,
+        writing_account,
+        donator,
+        ValidatedOperation::Donate,
+        voucher.amount,
+    )?;
+
+    // The voucher keeps its rent, only the amount it holds goes to the campaign.
+    **voucher_account.try_borrow_mut_lamports()? -= voucher.amount;
+    **writing_account.try_borrow_mut_lamports()? += voucher.amount;
+
+    voucher.redeemed = true;
+    voucher.serialize(&mut &mut voucher_account.data.borrow_mut()[..])?;
+    campaign_data.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
+
+    DonationTracking::find(program_id, writing_account, accounts).record(
+        program_id,
+        writing_account,
+        donator,
+        voucher.amount,
+    )?;
+
+    set_return_data(&campaign_data.amount_donated.to_le_bytes());
+
+    Ok(())
+}
+
+// Once a campaign stops accepting donations its vouchers can't be redeemed anymore, so the
+// admin takes back what they hold, rent included. Redeemed vouchers stay, they keep their
+// voucher id from being funded a second time. A pruned campaign leaves its vouchers behind,
+// those are closed by the admin stored in the voucher.
+fn close_voucher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input_data: VoucherRequest,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    let admin_account = next_account_info(accounts_iter)?;
+    let voucher_account = next_account_info(accounts_iter)?;
+
+    check_signer_account(program_id, writing_account, admin_account)?;
+
+    voucher_bump(
+        program_id,
+        writing_account,
+        voucher_account,
+        input_data.voucher_id,
+    )?;
+    if voucher_account.owner != program_id {
+        msg!("voucher_account isn't owned by program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let voucher = Voucher::try_from_slice(&voucher_account.data.borrow())?;
+
+    let campaign_closed = writing_account.owner != program_id || writing_account.data_is_empty();
+    if campaign_closed {
+        require_authority(admin_account, &voucher.admin)?;
+    } else {
+        let campaign_data = CampaignDetails::load(program_id, writing_account)?;
+        require_role(admin_account, &campaign_data, Role::Admin)?;
+    }
+
+    if voucher.redeemed {
+        msg!("Voucher {} was already redeemed", input_data.voucher_id);
+        return Err(CrowdfundingError::VoucherAlreadyRedeemed.into());
+    }
+
+    let lamports = voucher_account.lamports();
+    **voucher_account.try_borrow_mut_lamports()? -= lamports;
+    **admin_account.try_borrow_mut_lamports()? += lamports;
+    voucher_account.data.borrow_mut().fill(0);
+    voucher_account.realloc(0, false)?;
+    voucher_account.assign(&system_program::id());
+
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct DonorRequest {
+    pub donor: Pubkey,
+}
+
+fn set_donor_blocked(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input_data: DonorRequest,
+    blocked: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    // The admin pays for the account growing when a donor is blocked, and gets the rent back
+    // when the donor is unblocked.
+    let admin_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    check_signer_account(program_id, writing_account, admin_account)?;
+
+    let mut campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+    require_role(admin_account, &campaign_data, Role::Admin)?;
+
+    let position = campaign_data
+        .blocked_donors
+        .iter()
+        .position(|donor| *donor == input_data.donor);
+    match (blocked, position) {
+        (true, None) => {
+            if campaign_data.blocked_donors.len() >= MAX_BLOCKED_DONORS {
+                msg!("Can't block more than {} donors", MAX_BLOCKED_DONORS);
+                return Err(CrowdfundingError::BlockListFull.into());
+            }
+            campaign_data.blocked_donors.push(input_data.donor);
+        }
+        (false, Some(index)) => {
+            campaign_data.blocked_donors.remove(index);
+        }
+        // Blocking a blocked donor or unblocking a donor who isn't blocked changes nothing.
+        _ => return Ok(()),
+    }
+
+    resize_account(
+        writing_account,
+        admin_account,
+        system_program,
+        campaign_data.space()?,
+    )?;
+    campaign_data.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// The operations a campaign validator program is asked to approve.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy)]
+enum ValidatedOperation {
+    Donate,
+    Withdraw,
+}
+
+// The instruction data sent to a campaign validator program. Its accounts are the campaign
+// account and the account doing the operation, both read-only.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct ValidationRequest {
+    pub operation: ValidatedOperation,
+    pub actor: Pubkey,
+    pub amount: u64,
+}
+
+// When the campaign has a validator program, we ask it to approve the operation through a
+// CPI. The validator program account can be anywhere in the instruction accounts. If the
+// validator fails, the whole transaction fails with it.
+fn run_validator<'a>(
+    accounts: &[AccountInfo<'a>],
+    campaign_data: &CampaignDetails,
+    writing_account: &AccountInfo<'a>,
+    actor: &AccountInfo<'a>,
+    operation: ValidatedOperation,
+    amount: u64,
+) -> ProgramResult {
+    let validator_program = match campaign_data.validator_program {
+        Some(validator_program) => validator_program,
+        None => return Ok(()),
+    };
+    let validator_account = accounts
+        .iter()
+        .find(|account| *account.key == validator_program)
+        .ok_or_else(|| {
+            msg!(
+                "The campaign validator program {} is missing",
+                validator_program
+            );
+            ProgramError::NotEnoughAccountKeys
+        })?;
+
+    let request = ValidationRequest {
+        operation,
+        actor: *actor.key,
+        amount,
+    };
+    invoke(
+        &Instruction::new_with_bytes(
+            validator_program,
+            &request.try_to_vec()?,
+            vec![
+                AccountMeta::new_readonly(*writing_account.key, false),
+                AccountMeta::new_readonly(*actor.key, false),
+            ],
+        ),
+        &[
+            writing_account.clone(),
+            actor.clone(),
+            validator_account.clone(),
+        ],
+    )
+}
+
+// Free text written by the admin must fit in `max_len` bytes and can't hold control
+// characters, so it displays the same in every client.
+fn validate_text(text: &str, max_len: usize, field: &str) -> ProgramResult {
+    if text.len() > max_len {
+        msg!(
+            "Invalid instruction data, {} can't exceed {} bytes",
+            field,
+            max_len
+        );
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if text.chars().any(char::is_control) {
+        msg!(
+            "Invalid instruction data, {} can't contain control characters",
+            field
+        );
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(())
+}
+
+// Tells if `now` is past the campaign deadline, allowing CLOCK_TOLERANCE_SECS of skew.
+// A deadline of 0 means the campaign never ends.
+fn is_past_deadline(deadline: i64, now: i64) -> bool {
+    deadline != 0 && now > deadline.saturating_add(CLOCK_TOLERANCE_SECS)
+}
+
+// The campaign directory lets clients list every registered campaign page by page, without
+// scanning all the program accounts. The directory PDA counts the registered campaigns and
+// each page PDA lists DIRECTORY_PAGE_LEN of them in registration order. Closed campaigns
+// keep their spot, clients skip the accounts that don't hold a campaign anymore.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
+struct Directory {
+    pub count: u64,
+}
+
+impl Directory {
+    const SPACE: usize = 8;
+}
+
+// Only the first `count` entries are used.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
+struct DirectoryPage {
+    pub count: u8,
+    pub campaigns: [Pubkey; DIRECTORY_PAGE_LEN],
+}
+
+impl DirectoryPage {
+    const SPACE: usize = 1 + PUBKEY_BYTES * DIRECTORY_PAGE_LEN;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct DirectoryPageRequest {
+    pub page: u64,
+}
+
+// What DirectoryPage returns, `next_page` is None on the last page.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+struct DirectoryPageView {
+    pub campaigns: Vec<Pubkey>,
+    pub next_page: Option<u64>,
+}
+
+// Checks `directory_account` is the directory PDA and returns its bump.
+fn directory_bump(
+    program_id: &Pubkey,
+    directory_account: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let (expected_key, bump) = Pubkey::find_program_address(&[DIRECTORY_SEED], program_id);
+    if expected_key != *directory_account.key {
+        msg!("directory_account isn't the campaign directory PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump)
+}
+
+// Checks `page_account` is the PDA of directory page `page` and returns its bump.
+fn directory_page_bump(
+    program_id: &Pubkey,
+    page_account: &AccountInfo,
+    page: u64,
+) -> Result<u8, ProgramError> {
+    let (expected_key, bump) =
+        Pubkey::find_program_address(&[DIRECTORY_SEED, &page.to_le_bytes()], program_id);
+    if expected_key != *page_account.key {
+        msg!("page_account isn't the PDA of directory page {}", page);
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump)
+}
+
+// Reads a directory PDA, one that wasn't created yet reads as all zeros.
+fn read_directory_account<T: BorshDeserialize + Default>(
+    program_id: &Pubkey,
+    account: &AccountInfo,
+) -> Result<T, ProgramError> {
+    if account.data_is_empty() {
+        return Ok(T::default());
+    }
+    if account.owner != program_id {
+        msg!("{} isn't owned by program", account.key);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(T::try_from_slice(&account.data.borrow())?)
+}
+
+// The admin pays for the directory accounts the registration creates and for the campaign
+// growing with its directory index. The page to pass is the last one,
+// `count / DIRECTORY_PAGE_LEN` of the directory.
+fn register_campaign(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let writing_account = next_account_info(accounts_iter)?;
+    let admin_account = next_account_info(accounts_iter)?;
+    let directory_account = next_account_info(accounts_iter)?;
+    let page_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    check_signer_account(program_id, writing_account, admin_account)?;
+
+    let mut campaign_data = CampaignDetails::load(program_id, writing_account)?;
+
+    require_role(admin_account, &campaign_data, Role::Admin)?;
+
+    if let Some(index) = campaign_data.directory_index {
+        msg!("The campaign is already in the directory at {}", index);
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let directory_bump = directory_bump(program_id, directory_account)?;
+    let mut directory: Directory = read_directory_account(program_id, directory_account)?;
+    if directory_account.data_is_empty() {
+        create_pda_account(
+            admin_account,
+            directory_account,
+            system_program,
+            program_id,
+            Directory::SPACE,
+            &[DIRECTORY_SEED, &[directory_bump]],
+        )?;
+    }
+
+    let index = directory.count;
+    let page_index = index / DIRECTORY_PAGE_LEN as u64;
+    let page_bump = directory_page_bump(program_id, page_account, page_index)?;
+    let mut page: DirectoryPage = read_directory_account(program_id, page_account)?;
+    if page_account.data_is_empty() {
+        create_pda_account(
+            admin_account,
+            page_account,
+            system_program,
+            program_id,
+            DirectoryPage::SPACE,
+            &[DIRECTORY_SEED, &page_index.to_le_bytes(), &[page_bump]],
+        )?;
+    }
+
+    // The directory count tells which spot of the page is next, a page that doesn't agree was
+    // tampered with and we'd rather stop than overwrite or skip entries.
+    let slot = index % DIRECTORY_PAGE_LEN as u64;
+    let entry = page
+        .campaigns
+        .get_mut(page.count as usize)
+        .filter(|_| page.count as u64 == slot)
+        .ok_or_else(|| {
+            msg!(
+                "Directory page {} holds {} campaigns, expected {}",
+                page_index,
+                page.count,
+                slot
+            );
+            ProgramError::InvalidAccountData
+        })?;
+    *entry = *writing_account.key;
+    page.count += 1;
+    directory.count += 1;
+    campaign_data.directory_index = Some(index);
+
+    page.serialize(&mut &mut page_account.data.borrow_mut()[..])?;
+    directory.serialize(&mut &mut directory_account.data.borrow_mut()[..])?;
+    // The index makes the campaign bigger, the admin pays for that too.
+    resize_account(
+        writing_account,
+        admin_account,
+        system_program,
+        campaign_data.space()?,
+    )?;
+    campaign_data.serialize(&mut &mut writing_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn directory_page(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input_data: DirectoryPageRequest,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let directory_account = next_account_info(accounts_iter)?;
+    let page_account = next_account_info(accounts_iter)?;
+
+    directory_bump(program_id, directory_account)?;
+    directory_page_bump(program_id, page_account, input_data.page)?;
+
+    let directory: Directory = read_directory_account(program_id, directory_account)?;
+    let page: DirectoryPage = read_directory_account(program_id, page_account)?;
+
+    let view = DirectoryPageView {
+        campaigns: page.campaigns[..page.count as usize].to_vec(),
+        next_page: input_data.page.checked_add(1).filter(|next_page| {
+            next_page.saturating_mul(DIRECTORY_PAGE_LEN as u64) < directory.count
+        }),
+    };
+    set_return_data(&view.try_to_vec()?);
 
     Ok(())
 }
@@ -0,0 +1,279 @@
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use super::{
+    campaign, campaign_account, donate, instruction_data, logs, now, process, read_campaign,
+    TestAccount, PROGRAM_ID,
+};
+use crate::{
+    acl::{
+        check_signer_account, require_any_role, require_authority, require_cosigner, require_role,
+        require_signer, Role,
+    },
+    CrowdfundingError,
+};
+
+#[test]
+fn signer_is_required() {
+    let mut signer = TestAccount::wallet(0).signer();
+    let mut other = TestAccount::wallet(0);
+
+    assert_eq!(require_signer(&signer.info(), "signer"), Ok(()));
+    assert_eq!(
+        require_signer(&other.info(), "other"),
+        Err(ProgramError::IncorrectProgramId)
+    );
+}
+
+#[test]
+fn admin_role() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut other = TestAccount::wallet(0).signer();
+    let details = campaign(&admin);
+
+    assert_eq!(require_role(&admin.info(), &details, Role::Admin), Ok(()));
+    assert_eq!(
+        require_role(&other.info(), &details, Role::Admin),
+        Err(ProgramError::InvalidAccountData)
+    );
+    admin.is_signer = false;
+    assert_eq!(
+        require_role(&admin.info(), &details, Role::Admin),
+        Err(ProgramError::IncorrectProgramId)
+    );
+}
+
+#[test]
+fn cosigner_role() {
+    let admin = TestAccount::wallet(0).signer();
+    let mut cosigner = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+
+    // Nobody is the cosigner of a campaign without one.
+    assert_eq!(
+        require_role(&cosigner.info(), &details, Role::Cosigner),
+        Err(ProgramError::InvalidAccountData)
+    );
+    details.cosigner = Some(cosigner.key);
+    assert_eq!(
+        require_role(&cosigner.info(), &details, Role::Cosigner),
+        Ok(())
+    );
+    cosigner.is_signer = false;
+    assert_eq!(
+        require_role(&cosigner.info(), &details, Role::Cosigner),
+        Err(ProgramError::IncorrectProgramId)
+    );
+}
+
+#[test]
+fn cosigner_is_found_among_the_accounts() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut cosigner = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+
+    assert_eq!(require_cosigner(&[admin.info()], &details), Ok(()));
+
+    details.cosigner = Some(cosigner.key);
+    assert_eq!(
+        require_cosigner(&[admin.info()], &details),
+        Err(ProgramError::NotEnoughAccountKeys)
+    );
+    assert_eq!(
+        require_cosigner(&[admin.info(), cosigner.info()], &details),
+        Ok(())
+    );
+    cosigner.is_signer = false;
+    assert_eq!(
+        require_cosigner(&[admin.info(), cosigner.info()], &details),
+        Err(ProgramError::IncorrectProgramId)
+    );
+}
+
+#[test]
+fn guardian_role() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut guardian = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.guardian = Some(guardian.key);
+
+    assert_eq!(
+        require_role(&guardian.info(), &details, Role::Guardian),
+        Ok(())
+    );
+    // The guardian isn't the admin, and the other way around.
+    assert_eq!(
+        require_role(&guardian.info(), &details, Role::Admin),
+        Err(ProgramError::InvalidAccountData)
+    );
+    assert_eq!(
+        require_role(&admin.info(), &details, Role::Guardian),
+        Err(ProgramError::InvalidAccountData)
+    );
+
+    let either = [Role::Admin, Role::Guardian];
+    assert_eq!(require_any_role(&admin.info(), &details, &either), Ok(()));
+    assert_eq!(
+        require_any_role(&guardian.info(), &details, &either),
+        Ok(())
+    );
+    let mut other = TestAccount::wallet(0).signer();
+    assert_eq!(
+        require_any_role(&other.info(), &details, &either),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn authority_role() {
+    let mut authority = TestAccount::wallet(0).signer();
+    let key = authority.key;
+
+    assert_eq!(require_authority(&authority.info(), &key), Ok(()));
+    assert_eq!(
+        require_authority(&authority.info(), &Pubkey::new_unique()),
+        Err(ProgramError::InvalidAccountData)
+    );
+    authority.is_signer = false;
+    assert_eq!(
+        require_authority(&authority.info(), &key),
+        Err(ProgramError::IncorrectProgramId)
+    );
+}
+
+// Authority isn't stored on campaigns, asking a campaign for it is a bug in the handler.
+#[test]
+#[should_panic(expected = "the Authority role is checked by require_authority")]
+fn authority_is_not_a_campaign_role() {
+    let mut authority = TestAccount::wallet(0).signer();
+    let details = campaign(&authority);
+    let _ = require_role(&authority.info(), &details, Role::Authority);
+}
+
+#[test]
+fn withdrawals_need_the_cosigner() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut cosigner = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.cosigner = Some(cosigner.key);
+    let mut writing_account = campaign_account(details, 1_000);
+    let withdraw = instruction_data(1, 400u64);
+
+    assert_eq!(
+        process(&mut [&mut writing_account, &mut admin], &withdraw),
+        Err(ProgramError::NotEnoughAccountKeys)
+    );
+    process(
+        &mut [&mut writing_account, &mut admin, &mut cosigner],
+        &withdraw,
+    )
+    .unwrap();
+    assert_eq!(admin.lamports, 400);
+}
+
+#[test]
+fn the_guardian_can_end_the_campaign() {
+    let admin = TestAccount::wallet(0);
+    let mut guardian = TestAccount::wallet(0).signer();
+    let mut other = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.guardian = Some(guardian.key);
+    details.deadline = now() + 1_000;
+    let mut writing_account = campaign_account(details, 0);
+    let end_now = instruction_data(27, ());
+
+    assert_eq!(
+        process(&mut [&mut writing_account, &mut other], &end_now),
+        Err(ProgramError::InvalidAccountData)
+    );
+    process(&mut [&mut writing_account, &mut guardian], &end_now).unwrap();
+    assert!(read_campaign(&writing_account).deadline < now());
+}
+
+#[test]
+fn a_signer_is_never_the_program_or_the_campaign() {
+    let mut writing_account = TestAccount::wallet(0);
+    let mut program = TestAccount::wallet(0).signer().with_key(PROGRAM_ID);
+    let mut campaign_signer = TestAccount::wallet(0)
+        .signer()
+        .with_key(writing_account.key);
+    let mut signer = TestAccount::wallet(0).signer();
+
+    assert_eq!(
+        check_signer_account(&PROGRAM_ID, &writing_account.info(), &signer.info()),
+        Ok(())
+    );
+    assert_eq!(
+        check_signer_account(&PROGRAM_ID, &writing_account.info(), &program.info()),
+        Err(CrowdfundingError::InvalidAccountData.into())
+    );
+    assert_eq!(
+        check_signer_account(
+            &PROGRAM_ID,
+            &writing_account.info(),
+            &campaign_signer.info()
+        ),
+        Err(CrowdfundingError::InvalidAccountData.into())
+    );
+}
+
+#[test]
+fn handlers_reject_the_program_or_the_campaign_as_signer() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(campaign(&admin), 1_000);
+    let mut program = TestAccount::wallet(0).signer().with_key(PROGRAM_ID);
+    let mut campaign_signer = TestAccount::wallet(0)
+        .signer()
+        .with_key(writing_account.key);
+
+    assert_eq!(
+        donate(&mut writing_account, &mut program, 100),
+        Err(CrowdfundingError::InvalidAccountData.into())
+    );
+    assert_eq!(
+        donate(&mut writing_account, &mut campaign_signer, 100),
+        Err(CrowdfundingError::InvalidAccountData.into())
+    );
+    assert_eq!(
+        process(
+            &mut [&mut writing_account, &mut campaign_signer],
+            &instruction_data(1, 100u64)
+        ),
+        Err(CrowdfundingError::InvalidAccountData.into())
+    );
+}
+
+#[test]
+fn the_guardian_ending_the_campaign_logs_no_failure() {
+    let admin = TestAccount::wallet(0);
+    let mut guardian = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.guardian = Some(guardian.key);
+    let mut writing_account = campaign_account(details, 0);
+
+    process(
+        &mut [&mut writing_account, &mut guardian],
+        &instruction_data(27, ()),
+    )
+    .unwrap();
+    assert!(!logs().iter().any(|log| log.starts_with("Only the")));
+
+    let mut other = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.guardian = Some(guardian.key);
+    let mut writing_account = campaign_account(details, 0);
+    assert_eq!(
+        process(
+            &mut [&mut writing_account, &mut other],
+            &instruction_data(27, ()),
+        ),
+        Err(ProgramError::InvalidAccountData)
+    );
+    let failures: Vec<String> = logs()
+        .into_iter()
+        .filter(|log| log.starts_with("Only the"))
+        .collect();
+    assert_eq!(
+        failures,
+        ["Only the campaign Admin or Guardian can do this"]
+    );
+}
@@ -0,0 +1,85 @@
+use super::{
+    campaign, campaign_account, donate, instruction_data, now, process, read_campaign, return_data,
+    set_clock, TestAccount,
+};
+use crate::constants::{ACTIVITY_HALF_LIFE_SECS, ACTIVITY_POINTS_PER_DONATION, MAX_ACTIVITY_SCORE};
+
+const ACTIVITY_SCORE: u8 = 26;
+
+fn score(writing_account: &mut TestAccount) -> u64 {
+    process(
+        &mut [writing_account],
+        &instruction_data(ACTIVITY_SCORE, ()),
+    )
+    .unwrap();
+    u64::from_le_bytes(return_data().unwrap().try_into().unwrap())
+}
+
+#[test]
+fn rises_on_donations_and_halves_every_half_life() {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let start = now();
+    assert_eq!(score(&mut writing_account), 0);
+
+    donate(&mut writing_account, &mut donator, 1).unwrap();
+    donate(&mut writing_account, &mut donator, 1).unwrap();
+    assert_eq!(
+        score(&mut writing_account),
+        2 * ACTIVITY_POINTS_PER_DONATION
+    );
+
+    // Nothing decays before a whole half-life went by.
+    set_clock(start + ACTIVITY_HALF_LIFE_SECS - 1, 0);
+    assert_eq!(
+        score(&mut writing_account),
+        2 * ACTIVITY_POINTS_PER_DONATION
+    );
+    set_clock(start + ACTIVITY_HALF_LIFE_SECS, 0);
+    assert_eq!(score(&mut writing_account), ACTIVITY_POINTS_PER_DONATION);
+    set_clock(start + 2 * ACTIVITY_HALF_LIFE_SECS, 0);
+    assert_eq!(
+        score(&mut writing_account),
+        ACTIVITY_POINTS_PER_DONATION / 2
+    );
+
+    // A donation adds to the decayed score and restarts the decay from now.
+    donate(&mut writing_account, &mut donator, 1).unwrap();
+    assert_eq!(
+        score(&mut writing_account),
+        ACTIVITY_POINTS_PER_DONATION / 2 + ACTIVITY_POINTS_PER_DONATION
+    );
+    assert_eq!(
+        read_campaign(&writing_account).activity_updated_at,
+        start + 2 * ACTIVITY_HALF_LIFE_SECS
+    );
+}
+
+#[test]
+fn the_score_is_capped() {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.activity_score = MAX_ACTIVITY_SCORE;
+    details.activity_updated_at = now();
+    let mut writing_account = campaign_account(details, 0);
+
+    donate(&mut writing_account, &mut donator, 1).unwrap();
+    assert_eq!(score(&mut writing_account), MAX_ACTIVITY_SCORE);
+}
+
+#[test]
+fn decays_to_zero_without_overflowing() {
+    let admin = TestAccount::wallet(0);
+    let mut details = campaign(&admin);
+    details.activity_score = MAX_ACTIVITY_SCORE;
+    details.activity_updated_at = now();
+    let mut writing_account = campaign_account(details, 0);
+
+    set_clock(i64::MAX, 0);
+    assert_eq!(score(&mut writing_account), 0);
+    // A clock behind the last update doesn't count as time passing.
+    set_clock(i64::MIN, 0);
+    assert_eq!(score(&mut writing_account), MAX_ACTIVITY_SCORE);
+}
@@ -0,0 +1,104 @@
+use solana_program::{program_error::ProgramError, pubkey::Pubkey, rent::Rent};
+
+use super::{
+    balance, campaign, campaign_account, donate, instruction_data, process, read_campaign,
+    system_program_account, TestAccount,
+};
+use crate::{constants::MAX_BLOCKED_DONORS, CrowdfundingError, DonorRequest};
+
+const BLOCK_DONOR: u8 = 8;
+const UNBLOCK_DONOR: u8 = 9;
+
+fn set_blocked(
+    tag: u8,
+    writing_account: &mut TestAccount,
+    admin: &mut TestAccount,
+    donor: Pubkey,
+) -> Result<(), ProgramError> {
+    let mut system = system_program_account();
+    process(
+        &mut [writing_account, admin, &mut system],
+        &instruction_data(tag, DonorRequest { donor }),
+    )
+}
+
+#[test]
+fn a_blocked_donor_can_not_donate() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let size = writing_account.data().len();
+
+    set_blocked(BLOCK_DONOR, &mut writing_account, &mut admin, donator.key).unwrap();
+    assert_eq!(
+        read_campaign(&writing_account).blocked_donors,
+        vec![donator.key]
+    );
+    // The admin paid the rent of the longer list, the campaign balance didn't move.
+    assert_eq!(writing_account.data().len(), size + 32);
+    assert_eq!(balance(&writing_account), 0);
+
+    assert_eq!(
+        donate(&mut writing_account, &mut donator, 100),
+        Err(CrowdfundingError::DonorBlocked.into())
+    );
+    let mut other = TestAccount::wallet(0).signer();
+    donate(&mut writing_account, &mut other, 100).unwrap();
+}
+
+#[test]
+fn an_unblocked_donor_can_donate_again() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let size = writing_account.data().len();
+
+    set_blocked(BLOCK_DONOR, &mut writing_account, &mut admin, donator.key).unwrap();
+    set_blocked(UNBLOCK_DONOR, &mut writing_account, &mut admin, donator.key).unwrap();
+
+    assert!(read_campaign(&writing_account).blocked_donors.is_empty());
+    assert_eq!(writing_account.data().len(), size);
+    assert_eq!(
+        writing_account.lamports,
+        Rent::default().minimum_balance(size)
+    );
+    assert_eq!(admin.lamports, 1_000_000_000);
+    donate(&mut writing_account, &mut donator, 100).unwrap();
+}
+
+#[test]
+fn the_list_is_bounded() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let mut details = campaign(&admin);
+    details.blocked_donors = (0..MAX_BLOCKED_DONORS)
+        .map(|_| Pubkey::new_unique())
+        .collect();
+    let mut writing_account = campaign_account(details, 0);
+
+    assert_eq!(
+        set_blocked(
+            BLOCK_DONOR,
+            &mut writing_account,
+            &mut admin,
+            Pubkey::new_unique()
+        ),
+        Err(CrowdfundingError::BlockListFull.into())
+    );
+}
+
+#[test]
+fn only_the_admin_blocks_donors() {
+    let admin = TestAccount::wallet(0);
+    let mut other = TestAccount::wallet(1_000_000_000).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+
+    assert_eq!(
+        set_blocked(
+            BLOCK_DONOR,
+            &mut writing_account,
+            &mut other,
+            Pubkey::new_unique()
+        ),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
@@ -0,0 +1,157 @@
+use borsh::BorshSerialize;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use super::{campaign, TestAccount};
+use crate::{
+    client::{
+        decode_campaign, read_admin, read_amount_donated, read_goal, ADMIN_OFFSET,
+        AMOUNT_DONATED_RELATIVE_OFFSET, GOAL_RELATIVE_OFFSET, NAME_OFFSET, VERSION_OFFSET,
+    },
+    CampaignStatus,
+};
+
+fn valid_bytes() -> (Pubkey, Vec<u8>) {
+    let admin = TestAccount::wallet(0);
+    let mut details = campaign(&admin);
+    details.amount_donated = 1_500;
+    details.goal = 2_000;
+    details.final_status = Some(CampaignStatus::Failed);
+    (admin.key, details.try_to_vec().unwrap())
+}
+
+#[test]
+fn decodes_a_valid_campaign() {
+    let (admin, data) = valid_bytes();
+
+    let decoded = decode_campaign(&data).unwrap();
+    assert_eq!(decoded.admin, admin);
+    assert_eq!(decoded.amount_donated, 1_500);
+    assert_eq!(decoded.goal, 2_000);
+    assert_eq!(decoded.final_status, Some(CampaignStatus::Failed));
+
+    assert_eq!(read_admin(&data), Some(admin));
+    assert_eq!(read_amount_donated(&data), Some(1_500));
+    assert_eq!(read_goal(&data), Some(2_000));
+}
+
+#[test]
+fn pins_the_offsets() {
+    assert_eq!(VERSION_OFFSET, 0);
+    assert_eq!(ADMIN_OFFSET, 1);
+    assert_eq!(NAME_OFFSET, 33);
+    assert_eq!(AMOUNT_DONATED_RELATIVE_OFFSET, 0);
+    assert_eq!(GOAL_RELATIVE_OFFSET, 8);
+}
+
+#[test]
+fn reads_the_same_fields_as_the_full_decode() {
+    let admin = TestAccount::wallet(0);
+    // The strings move the fields after them, so we try a few lengths.
+    let long_description = "d".repeat(300);
+    for (name, description, image_link) in [
+        ("", "", ""),
+        ("Campaign", "A campaign", "https://example.com/image.png"),
+        ("Été", long_description.as_str(), "i"),
+    ] {
+        let mut details = campaign(&admin);
+        details.name = name.to_string();
+        details.description = description.to_string();
+        details.image_link = image_link.to_string();
+        details.amount_donated = 123_456;
+        details.goal = u64::MAX - 1;
+        let data = details.try_to_vec().unwrap();
+
+        let decoded = decode_campaign(&data).unwrap();
+        assert_eq!(read_admin(&data), Some(decoded.admin), "{}", name);
+        assert_eq!(
+            read_amount_donated(&data),
+            Some(decoded.amount_donated),
+            "{}",
+            name
+        );
+        assert_eq!(read_goal(&data), Some(decoded.goal), "{}", name);
+    }
+}
+
+#[test]
+fn rejects_another_version() {
+    let (_, mut data) = valid_bytes();
+    data[0] = 0;
+
+    assert_eq!(
+        decode_campaign(&data).err(),
+        Some(ProgramError::InvalidAccountData)
+    );
+    assert_eq!(read_admin(&data), None);
+    assert_eq!(read_amount_donated(&data), None);
+    assert_eq!(read_goal(&data), None);
+}
+
+#[test]
+fn rejects_truncated_bytes() {
+    let (_, data) = valid_bytes();
+
+    assert_eq!(
+        decode_campaign(&data[..data.len() - 1]).err(),
+        Some(ProgramError::InvalidAccountData)
+    );
+    assert_eq!(
+        decode_campaign(&[]).err(),
+        Some(ProgramError::InvalidAccountData)
+    );
+    assert_eq!(read_admin(&data[..10]), None);
+    assert_eq!(read_goal(&data[..40]), None);
+}
+
+#[test]
+fn rejects_trailing_bytes() {
+    let (_, mut data) = valid_bytes();
+    data.push(0);
+
+    assert_eq!(
+        decode_campaign(&data).err(),
+        Some(ProgramError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn rejects_corrupted_bytes() {
+    let (_, data) = valid_bytes();
+
+    // The name length claims more bytes than the account holds.
+    let mut too_long = data.clone();
+    too_long[33..37].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(
+        decode_campaign(&too_long).err(),
+        Some(ProgramError::InvalidAccountData)
+    );
+    assert_eq!(read_amount_donated(&too_long), None);
+
+    // The name isn't UTF-8.
+    let mut not_utf8 = data.clone();
+    not_utf8[37] = 0xff;
+    assert_eq!(
+        decode_campaign(&not_utf8).err(),
+        Some(ProgramError::InvalidAccountData)
+    );
+
+    // final_status is the only Some in the account, its tag is 1 and nothing else is valid.
+    let status_tag = data.len() - data.iter().rev().position(|byte| *byte == 1).unwrap() - 1;
+    let mut bad_tag = data;
+    bad_tag[status_tag] = 2;
+    assert_eq!(
+        decode_campaign(&bad_tag).err(),
+        Some(ProgramError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn rejects_a_campaign_without_admin() {
+    let (_, mut data) = valid_bytes();
+    data[1..33].fill(0);
+
+    assert_eq!(
+        decode_campaign(&data).err(),
+        Some(ProgramError::UninitializedAccount)
+    );
+}
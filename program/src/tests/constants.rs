@@ -0,0 +1,99 @@
+// Each limit of constants.rs is accepted at the limit and rejected one past it.
+
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use super::{
+    campaign, campaign_account, create_campaign, instruction_data, now, process, TestAccount,
+};
+use crate::{
+    constants::{
+        MAX_BLOCKED_DONORS, MAX_ENDING_SOON_WINDOW_SECS, MAX_GOAL, MAX_REFERRAL_BPS,
+        MAX_RENT_BUFFER_BPS, MAX_THANK_YOU_LEN, MAX_VESTING_DURATION_SECS, MAX_WITHDRAW_NOTE_LEN,
+        MIN_INITIAL_FUNDING,
+    },
+    CampaignDetails, WithdrawWithNoteRequest,
+};
+
+// Creates a campaign with `edit` applied, at the limit and one past it.
+fn check_limit(edit: impl Fn(&mut CampaignDetails, bool)) {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+
+    edit(&mut details, false);
+    assert!(create_campaign(&mut admin, &details, MIN_INITIAL_FUNDING).is_ok());
+
+    edit(&mut details, true);
+    assert_eq!(
+        create_campaign(&mut admin, &details, MIN_INITIAL_FUNDING).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn goals_and_caps_up_to_max_goal() {
+    check_limit(|details, past| details.goal = MAX_GOAL + past as u64);
+    check_limit(|details, past| details.hard_cap = MAX_GOAL + past as u64);
+}
+
+#[test]
+fn rent_buffer_up_to_max_rent_buffer_bps() {
+    check_limit(|details, past| details.rent_buffer_bps = MAX_RENT_BUFFER_BPS + past as u16);
+}
+
+#[test]
+fn referral_up_to_max_referral_bps() {
+    check_limit(|details, past| details.referral_bps = MAX_REFERRAL_BPS + past as u16);
+}
+
+#[test]
+fn vesting_up_to_max_vesting_duration() {
+    check_limit(|details, past| details.vesting_duration = MAX_VESTING_DURATION_SECS + past as i64);
+}
+
+#[test]
+fn ending_soon_window_up_to_its_max() {
+    check_limit(|details, past| {
+        details.deadline = now() + 1_000;
+        details.ending_soon_window = MAX_ENDING_SOON_WINDOW_SECS + past as i64;
+    });
+}
+
+#[test]
+fn thank_you_up_to_max_thank_you_len() {
+    check_limit(|details, past| details.thank_you = "a".repeat(MAX_THANK_YOU_LEN + past as usize));
+}
+
+#[test]
+fn blocked_donors_up_to_max_blocked_donors() {
+    check_limit(|details, past| {
+        details.blocked_donors = (0..MAX_BLOCKED_DONORS + past as usize)
+            .map(|_| Pubkey::new_unique())
+            .collect()
+    });
+}
+
+#[test]
+fn withdraw_note_up_to_max_withdraw_note_len() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 1_000);
+
+    for (len, result) in [
+        (MAX_WITHDRAW_NOTE_LEN, Ok(())),
+        (
+            MAX_WITHDRAW_NOTE_LEN + 1,
+            Err(ProgramError::InvalidInstructionData),
+        ),
+    ] {
+        let request = WithdrawWithNoteRequest {
+            amount: 1,
+            note: "a".repeat(len),
+        };
+        assert_eq!(
+            process(
+                &mut [&mut writing_account, &mut admin],
+                &instruction_data(24, request)
+            ),
+            result
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use borsh::BorshSerialize;
+use solana_program::{program_error::ProgramError, rent::Rent};
+
+use super::{
+    campaign, campaign_account, donate, instruction_data, process, TestAccount, PROGRAM_ID,
+};
+use crate::{constants::MIN_INITIAL_FUNDING, CrowdfundingError};
+
+// Runs CreateCampaign on an account of `space` bytes, funded generously for that size.
+fn create_with_space(space: usize) -> Result<(), ProgramError> {
+    let mut admin = TestAccount::wallet(0).signer();
+    let details = campaign(&admin);
+    let lamports = Rent::default().minimum_balance(space) + MIN_INITIAL_FUNDING * 10;
+    let mut writing_account = TestAccount::new(PROGRAM_ID, lamports, &vec![0; space]);
+    process(
+        &mut [&mut writing_account, &mut admin],
+        &instruction_data(0, &details),
+    )
+}
+
+fn campaign_space() -> usize {
+    campaign(&TestAccount::wallet(0))
+        .try_to_vec()
+        .unwrap()
+        .len()
+}
+
+#[test]
+fn accepts_an_account_of_the_exact_size() {
+    assert_eq!(create_with_space(campaign_space()), Ok(()));
+}
+
+#[test]
+fn rejects_an_undersized_account() {
+    assert_eq!(
+        create_with_space(campaign_space() - 1),
+        Err(CrowdfundingError::AccountSizeMismatch.into())
+    );
+}
+
+#[test]
+fn rejects_an_oversized_account() {
+    assert_eq!(
+        create_with_space(campaign_space() + 1),
+        Err(CrowdfundingError::AccountSizeMismatch.into())
+    );
+}
+
+// A campaign that somehow ended up with spare bytes can't take donations either.
+#[test]
+fn donations_reject_a_campaign_with_spare_bytes() {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut data = writing_account.data().to_vec();
+    data.push(0);
+    writing_account.set_data(&data);
+
+    assert_eq!(
+        donate(&mut writing_account, &mut donator, 100),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
+
+// Runs CreateCampaign on an account of the right size holding `balance` above its rent.
+fn create_with_balance(balance: i64) -> Result<(), ProgramError> {
+    let mut admin = TestAccount::wallet(0).signer();
+    let details = campaign(&admin);
+    let rent = Rent::default().minimum_balance(campaign_space());
+    let mut writing_account = TestAccount::new(
+        PROGRAM_ID,
+        (rent as i64 + balance) as u64,
+        &vec![0; campaign_space()],
+    );
+    process(
+        &mut [&mut writing_account, &mut admin],
+        &instruction_data(0, &details),
+    )
+}
+
+#[test]
+fn accepts_exactly_the_minimum_initial_funding() {
+    assert_eq!(create_with_balance(MIN_INITIAL_FUNDING as i64), Ok(()));
+}
+
+#[test]
+fn rejects_less_than_the_minimum_initial_funding() {
+    for balance in [MIN_INITIAL_FUNDING as i64 - 1, 0] {
+        assert_eq!(
+            create_with_balance(balance),
+            Err(CrowdfundingError::InsufficientInitialFunding.into())
+        );
+    }
+    assert_eq!(
+        create_with_balance(-1),
+        Err(ProgramError::InsufficientFunds)
+    );
+}
@@ -0,0 +1,117 @@
+use solana_program::{program_error::ProgramError, system_instruction::SystemError};
+
+use super::{
+    balance, campaign, campaign_account, instruction_data, process, read_campaign,
+    system_program_account, TestAccount, PROGRAM_ID,
+};
+use crate::{CreateAndDonateRequest, CrowdfundingError};
+
+const CREATE_AND_DONATE: u8 = 17;
+
+fn create_and_donate(
+    writing_account: &mut TestAccount,
+    donator_program_account: &mut TestAccount,
+    donator: &mut TestAccount,
+    amount: u64,
+) -> Result<(), ProgramError> {
+    process(
+        &mut [
+            writing_account,
+            donator_program_account,
+            donator,
+            &mut system_program_account(),
+        ],
+        &instruction_data(CREATE_AND_DONATE, CreateAndDonateRequest { amount }),
+    )
+}
+
+#[test]
+fn creates_the_donator_program_account_and_donates_it() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut donator_program_account = TestAccount::wallet(0).signer();
+    let mut donator = TestAccount::wallet(1_000).signer();
+
+    create_and_donate(
+        &mut writing_account,
+        &mut donator_program_account,
+        &mut donator,
+        600,
+    )
+    .unwrap();
+
+    assert_eq!(donator.lamports, 400);
+    assert_eq!(balance(&writing_account), 600);
+    assert_eq!(read_campaign(&writing_account).amount_donated, 600);
+    // The account was created for the program and swept right away.
+    assert_eq!(donator_program_account.owner, PROGRAM_ID);
+    assert_eq!(donator_program_account.lamports, 0);
+}
+
+#[test]
+fn the_new_account_must_sign() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut donator_program_account = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(1_000).signer();
+
+    assert_eq!(
+        create_and_donate(
+            &mut writing_account,
+            &mut donator_program_account,
+            &mut donator,
+            600,
+        ),
+        Err(ProgramError::IncorrectProgramId)
+    );
+    assert_eq!(donator.lamports, 1_000);
+}
+
+#[test]
+fn fails_when_the_account_exists_or_the_donator_is_short() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut donator = TestAccount::wallet(1_000).signer();
+
+    let mut existing = TestAccount::wallet(1).signer();
+    assert_eq!(
+        create_and_donate(&mut writing_account, &mut existing, &mut donator, 600),
+        Err(ProgramError::Custom(
+            SystemError::AccountAlreadyInUse as u32
+        ))
+    );
+
+    let mut donator_program_account = TestAccount::wallet(0).signer();
+    assert_eq!(
+        create_and_donate(
+            &mut writing_account,
+            &mut donator_program_account,
+            &mut donator,
+            1_001,
+        ),
+        Err(ProgramError::Custom(
+            SystemError::ResultWithNegativeLamports as u32
+        ))
+    );
+    assert_eq!(read_campaign(&writing_account).amount_donated, 0);
+}
+
+#[test]
+fn rejects_zero_before_creating_anything() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut donator_program_account = TestAccount::wallet(0).signer();
+    let mut donator = TestAccount::wallet(1_000).signer();
+
+    assert_eq!(
+        create_and_donate(
+            &mut writing_account,
+            &mut donator_program_account,
+            &mut donator,
+            0,
+        ),
+        Err(CrowdfundingError::InvalidAmount.into())
+    );
+    assert_ne!(donator_program_account.owner, PROGRAM_ID);
+    assert_eq!(read_campaign(&writing_account).amount_donated, 0);
+}
@@ -0,0 +1,219 @@
+use solana_program::program_error::ProgramError;
+
+use super::{
+    campaign, campaign_account, create_campaign, donate, instruction_data, logs, now, process,
+    read_campaign, set_clock, TestAccount,
+};
+use crate::{
+    constants::{CLOCK_TOLERANCE_SECS, MAX_ENDING_SOON_WINDOW_SECS, MIN_INITIAL_FUNDING},
+    is_past_deadline, CrowdfundingError,
+};
+
+// Donates one lamport at `at` to a campaign whose deadline is `deadline`.
+fn donate_at(deadline: i64, at: i64) -> Result<(), ProgramError> {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.deadline = deadline;
+    let mut writing_account = campaign_account(details, 0);
+
+    set_clock(at, 0);
+    donate(&mut writing_account, &mut donator, 1)?;
+    assert_eq!(read_campaign(&writing_account).amount_donated, 1);
+    Ok(())
+}
+
+#[test]
+fn donations_are_accepted_up_to_the_tolerance_after_the_deadline() {
+    let deadline = now() + 100;
+
+    assert_eq!(donate_at(deadline, deadline - CLOCK_TOLERANCE_SECS), Ok(()));
+    assert_eq!(donate_at(deadline, deadline), Ok(()));
+    assert_eq!(donate_at(deadline, deadline + CLOCK_TOLERANCE_SECS), Ok(()));
+}
+
+#[test]
+fn donations_are_rejected_past_the_tolerance() {
+    let deadline = now() + 100;
+
+    assert_eq!(
+        donate_at(deadline, deadline + CLOCK_TOLERANCE_SECS + 1),
+        Err(CrowdfundingError::CampaignEnded.into())
+    );
+}
+
+#[test]
+fn a_campaign_without_deadline_never_ends() {
+    assert!(!is_past_deadline(0, i64::MAX));
+    assert_eq!(donate_at(0, i64::MAX), Ok(()));
+}
+
+#[test]
+fn the_tolerance_does_not_overflow() {
+    assert!(!is_past_deadline(i64::MAX, i64::MAX));
+    assert!(is_past_deadline(1, 2 + CLOCK_TOLERANCE_SECS));
+}
+
+const DEADLINE: i64 = 2_000_000;
+const WINDOW: i64 = 3_600;
+const END_NOW: u8 = 27;
+
+fn ending_soon_logs() -> usize {
+    logs()
+        .iter()
+        .filter(|log| log.starts_with("CF:ENDING_SOON"))
+        .count()
+}
+
+#[test]
+fn logs_ending_soon_once_inside_the_window() {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.deadline = DEADLINE;
+    details.ending_soon_window = WINDOW;
+    let mut writing_account = campaign_account(details, 0);
+
+    // Outside the window nothing is logged.
+    set_clock(DEADLINE - WINDOW - 1, 0);
+    donate(&mut writing_account, &mut donator, 1).unwrap();
+    assert_eq!(ending_soon_logs(), 0);
+    assert!(!read_campaign(&writing_account).ending_soon_notified);
+
+    // The first donation inside it logs, the next ones don't.
+    set_clock(DEADLINE - WINDOW, 0);
+    donate(&mut writing_account, &mut donator, 1).unwrap();
+    assert!(logs().contains(&format!("CF:ENDING_SOON {}", DEADLINE)));
+    set_clock(DEADLINE - 1, 0);
+    donate(&mut writing_account, &mut donator, 1).unwrap();
+    assert_eq!(ending_soon_logs(), 1);
+    assert!(read_campaign(&writing_account).ending_soon_notified);
+}
+
+#[test]
+fn no_window_means_no_log() {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.deadline = DEADLINE;
+    let mut writing_account = campaign_account(details, 0);
+
+    set_clock(DEADLINE - 1, 0);
+    donate(&mut writing_account, &mut donator, 1).unwrap();
+    assert_eq!(ending_soon_logs(), 0);
+}
+
+#[test]
+fn the_window_is_validated() {
+    let mut admin = TestAccount::wallet(0).signer();
+    for (deadline, window) in [
+        (DEADLINE, -1),
+        (DEADLINE, MAX_ENDING_SOON_WINDOW_SECS + 1),
+        // A window needs a deadline to count from.
+        (0, WINDOW),
+    ] {
+        let mut details = campaign(&admin);
+        details.deadline = deadline;
+        details.ending_soon_window = window;
+        assert_eq!(
+            create_campaign(&mut admin, &details, MIN_INITIAL_FUNDING).err(),
+            Some(ProgramError::InvalidInstructionData),
+            "deadline {} window {}",
+            deadline,
+            window
+        );
+    }
+
+    let mut details = campaign(&admin);
+    details.deadline = DEADLINE;
+    details.ending_soon_window = MAX_ENDING_SOON_WINDOW_SECS;
+    // A new campaign starts without the log sent, whatever the instruction says.
+    details.ending_soon_notified = true;
+    let writing_account = create_campaign(&mut admin, &details, MIN_INITIAL_FUNDING).unwrap();
+    assert!(!read_campaign(&writing_account).ending_soon_notified);
+}
+
+fn end_now(writing_account: &mut TestAccount, admin: &mut TestAccount) -> Result<(), ProgramError> {
+    process(
+        &mut [writing_account, admin],
+        &instruction_data(END_NOW, ()),
+    )
+}
+
+#[test]
+fn ending_early_blocks_donations() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.deadline = DEADLINE;
+    let mut writing_account = campaign_account(details, 0);
+    donate(&mut writing_account, &mut donator, 100).unwrap();
+
+    end_now(&mut writing_account, &mut admin).unwrap();
+    assert!(is_past_deadline(
+        read_campaign(&writing_account).deadline,
+        now()
+    ));
+    assert!(logs().contains(&format!("CF:ENDED_EARLY {}", now())));
+
+    assert_eq!(
+        donate(&mut writing_account, &mut donator, 100),
+        Err(CrowdfundingError::CampaignEnded.into())
+    );
+    assert_eq!(read_campaign(&writing_account).amount_donated, 100);
+}
+
+#[test]
+fn ends_a_campaign_without_deadline() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+
+    end_now(&mut writing_account, &mut admin).unwrap();
+    assert_eq!(
+        donate(&mut writing_account, &mut donator, 100),
+        Err(CrowdfundingError::CampaignEnded.into())
+    );
+}
+
+#[test]
+fn an_ended_campaign_can_not_end_again() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    end_now(&mut writing_account, &mut admin).unwrap();
+    let deadline = read_campaign(&writing_account).deadline;
+
+    set_clock(now() + 10, 0);
+    assert_eq!(
+        end_now(&mut writing_account, &mut admin),
+        Err(CrowdfundingError::CampaignEnded.into())
+    );
+    assert_eq!(read_campaign(&writing_account).deadline, deadline);
+
+    let mut details = campaign(&admin);
+    details.deadline = DEADLINE;
+    let mut expired = campaign_account(details, 0);
+    set_clock(DEADLINE + CLOCK_TOLERANCE_SECS + 1, 0);
+    assert_eq!(
+        end_now(&mut expired, &mut admin),
+        Err(CrowdfundingError::CampaignEnded.into())
+    );
+}
+
+#[test]
+fn only_the_signing_admin_ends_early() {
+    let admin = TestAccount::wallet(0).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+
+    let mut other = TestAccount::wallet(0).signer();
+    assert_eq!(
+        end_now(&mut writing_account, &mut other),
+        Err(ProgramError::InvalidAccountData)
+    );
+    let mut not_signing = TestAccount::wallet(0).with_key(admin.key);
+    assert_eq!(
+        end_now(&mut writing_account, &mut not_signing),
+        Err(ProgramError::IncorrectProgramId)
+    );
+    assert_eq!(read_campaign(&writing_account).deadline, 0);
+}
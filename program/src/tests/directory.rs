@@ -0,0 +1,144 @@
+use borsh::BorshDeserialize;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use super::{
+    campaign, campaign_account, instruction_data, process, read_campaign, return_data,
+    system_program_account, TestAccount, PROGRAM_ID,
+};
+use crate::{
+    constants::{DIRECTORY_PAGE_LEN, DIRECTORY_SEED},
+    DirectoryPageRequest, DirectoryPageView,
+};
+
+const REGISTER_CAMPAIGN: u8 = 22;
+const DIRECTORY_PAGE: u8 = 23;
+
+fn directory_account() -> TestAccount {
+    let (key, _) = Pubkey::find_program_address(&[DIRECTORY_SEED], &PROGRAM_ID);
+    TestAccount::wallet(0).with_key(key)
+}
+
+fn page_account(page: u64) -> TestAccount {
+    let (key, _) =
+        Pubkey::find_program_address(&[DIRECTORY_SEED, &page.to_le_bytes()], &PROGRAM_ID);
+    TestAccount::wallet(0).with_key(key)
+}
+
+fn register(
+    writing_account: &mut TestAccount,
+    admin: &mut TestAccount,
+    directory: &mut TestAccount,
+    page: &mut TestAccount,
+) -> Result<(), ProgramError> {
+    process(
+        &mut [
+            writing_account,
+            admin,
+            directory,
+            page,
+            &mut system_program_account(),
+        ],
+        &instruction_data(REGISTER_CAMPAIGN, ()),
+    )
+}
+
+fn read_page(
+    directory: &mut TestAccount,
+    page_account: &mut TestAccount,
+    page: u64,
+) -> DirectoryPageView {
+    process(
+        &mut [directory, page_account],
+        &instruction_data(DIRECTORY_PAGE, DirectoryPageRequest { page }),
+    )
+    .unwrap();
+    DirectoryPageView::try_from_slice(&return_data().unwrap()).unwrap()
+}
+
+#[test]
+fn paginates_the_registered_campaigns() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let mut directory = directory_account();
+    let mut pages = [page_account(0), page_account(1)];
+
+    let empty = read_page(&mut directory, &mut pages[0], 0);
+    assert!(empty.campaigns.is_empty());
+    assert_eq!(empty.next_page, None);
+
+    let mut registered = Vec::new();
+    for index in 0..DIRECTORY_PAGE_LEN + 2 {
+        let mut writing_account = campaign_account(campaign(&admin), 0);
+        let page = &mut pages[index / DIRECTORY_PAGE_LEN];
+        register(&mut writing_account, &mut admin, &mut directory, page).unwrap();
+        assert_eq!(
+            read_campaign(&writing_account).directory_index,
+            Some(index as u64)
+        );
+        registered.push(writing_account.key);
+    }
+
+    let first = read_page(&mut directory, &mut pages[0], 0);
+    assert_eq!(first.campaigns, registered[..DIRECTORY_PAGE_LEN]);
+    assert_eq!(first.next_page, Some(1));
+    let second = read_page(&mut directory, &mut pages[1], 1);
+    assert_eq!(second.campaigns, registered[DIRECTORY_PAGE_LEN..]);
+    assert_eq!(second.next_page, None);
+}
+
+#[test]
+fn a_campaign_is_registered_once() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let mut directory = directory_account();
+    let mut page = page_account(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+
+    register(&mut writing_account, &mut admin, &mut directory, &mut page).unwrap();
+    assert_eq!(
+        register(&mut writing_account, &mut admin, &mut directory, &mut page),
+        Err(ProgramError::AccountAlreadyInitialized)
+    );
+    assert_eq!(read_page(&mut directory, &mut page, 0).campaigns.len(), 1);
+}
+
+#[test]
+fn the_page_must_be_the_last_one() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let mut directory = directory_account();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+
+    assert_eq!(
+        register(
+            &mut writing_account,
+            &mut admin,
+            &mut directory,
+            &mut page_account(1)
+        ),
+        Err(ProgramError::InvalidSeeds)
+    );
+}
+
+#[test]
+fn rejects_a_page_out_of_sync_with_the_directory() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let mut directory = directory_account();
+    let mut page = page_account(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    register(&mut writing_account, &mut admin, &mut directory, &mut page).unwrap();
+
+    // A directory count back at 0 in front of a full page, or of one with a single entry.
+    directory.set_data(&0u64.to_le_bytes());
+    for count in [DIRECTORY_PAGE_LEN as u8, 1] {
+        let mut data = page.data().to_vec();
+        data[0] = count;
+        page.set_data(&data);
+
+        let mut writing_account = campaign_account(campaign(&admin), 0);
+        assert_eq!(
+            register(&mut writing_account, &mut admin, &mut directory, &mut page),
+            Err(ProgramError::InvalidAccountData),
+            "page count {}",
+            count
+        );
+        assert_eq!(read_campaign(&writing_account).directory_index, None);
+    }
+}
@@ -0,0 +1,105 @@
+use borsh::BorshDeserialize;
+use solana_program::program_error::ProgramError;
+
+use super::{
+    campaign, campaign_account, donate, instruction_data, process, read_campaign, return_data,
+    TestAccount,
+};
+use crate::{
+    constants::{DONATION_BUCKETS, DONATION_BUCKET_BOUNDS},
+    CrowdfundingError,
+};
+
+fn returned_total() -> u64 {
+    u64::from_le_bytes(return_data().unwrap().try_into().unwrap())
+}
+
+#[test]
+fn returns_the_new_total() {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.amount_donated = 1_000;
+    let mut writing_account = campaign_account(details, 1_000);
+
+    donate(&mut writing_account, &mut donator, 250).unwrap();
+    assert_eq!(returned_total(), 1_250);
+
+    donate(&mut writing_account, &mut donator, 50).unwrap();
+    assert_eq!(returned_total(), 1_300);
+    assert_eq!(read_campaign(&writing_account).amount_donated, 1_300);
+}
+
+#[test]
+fn a_failed_donation_returns_nothing() {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+
+    assert!(donate(&mut writing_account, &mut donator, 0).is_err());
+    assert_eq!(return_data(), None);
+}
+
+#[test]
+fn rejects_a_zero_donation() {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+
+    assert_eq!(
+        donate(&mut writing_account, &mut donator, 0),
+        Err(CrowdfundingError::InvalidAmount.into())
+    );
+    let details = read_campaign(&writing_account);
+    assert_eq!(details.amount_donated, 0);
+    assert_eq!(details.donation_histogram, [0; DONATION_BUCKETS]);
+}
+
+fn histogram(writing_account: &mut TestAccount) -> [u32; DONATION_BUCKETS] {
+    process(&mut [writing_account], &instruction_data(14, ())).unwrap();
+    <[u32; DONATION_BUCKETS]>::try_from_slice(&return_data().unwrap()).unwrap()
+}
+
+#[test]
+fn counts_donations_in_their_size_bucket() {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    assert_eq!(histogram(&mut writing_account), [0; DONATION_BUCKETS]);
+
+    let [one, ten, hundred] = DONATION_BUCKET_BOUNDS;
+    for amount in [
+        1,
+        one - 1,
+        one,
+        ten - 1,
+        ten,
+        hundred - 1,
+        hundred,
+        hundred * 5,
+    ] {
+        donate(&mut writing_account, &mut donator, amount).unwrap();
+    }
+
+    assert_eq!(histogram(&mut writing_account), [2, 2, 2, 2]);
+}
+
+#[test]
+fn a_full_bucket_rejects_the_donation() {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.donation_histogram[0] = u32::MAX;
+    let mut writing_account = campaign_account(details, 0);
+
+    assert_eq!(
+        donate(&mut writing_account, &mut donator, 1),
+        Err(ProgramError::InvalidArgument)
+    );
+    donate(
+        &mut writing_account,
+        &mut donator,
+        DONATION_BUCKET_BOUNDS[0],
+    )
+    .unwrap();
+}
@@ -0,0 +1,89 @@
+use borsh::BorshDeserialize;
+use solana_program::program_error::ProgramError;
+
+use super::{
+    campaign, campaign_account, campaign_pda, donate, donate_with, set_clock,
+    system_program_account, TestAccount, PROGRAM_ID,
+};
+use crate::{
+    constants::{EPOCH_DONATIONS_LEN, EPOCH_DONATIONS_SEED},
+    EpochDonations,
+};
+
+// The lamports the PDA holds for `epoch`, a slot left by another epoch counts as nothing.
+fn donated_in(epoch_donations_account: &TestAccount, epoch: u64) -> u64 {
+    let epochs = EpochDonations::try_from_slice(epoch_donations_account.data()).unwrap();
+    let slot = epochs.epochs[(epoch % EPOCH_DONATIONS_LEN as u64) as usize];
+    if slot.epoch == epoch {
+        slot.amount
+    } else {
+        0
+    }
+}
+
+fn donate_in(
+    epoch: u64,
+    writing_account: &mut TestAccount,
+    epoch_donations_account: &mut TestAccount,
+    amount: u64,
+) {
+    let mut donator = TestAccount::wallet(1_000_000_000).signer();
+    set_clock(1_000_000, epoch);
+    donate_with(
+        writing_account,
+        &mut donator,
+        amount,
+        &mut [epoch_donations_account, &mut system_program_account()],
+    )
+    .unwrap();
+}
+
+#[test]
+fn attributes_donations_to_their_epoch() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut epoch_donations_account = campaign_pda(EPOCH_DONATIONS_SEED, &writing_account);
+
+    donate_in(100, &mut writing_account, &mut epoch_donations_account, 300);
+    assert_eq!(epoch_donations_account.owner, PROGRAM_ID);
+    donate_in(100, &mut writing_account, &mut epoch_donations_account, 200);
+    donate_in(101, &mut writing_account, &mut epoch_donations_account, 50);
+
+    assert_eq!(donated_in(&epoch_donations_account, 100), 500);
+    assert_eq!(donated_in(&epoch_donations_account, 101), 50);
+    assert_eq!(donated_in(&epoch_donations_account, 102), 0);
+}
+
+#[test]
+fn only_the_latest_epochs_are_kept() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut epoch_donations_account = campaign_pda(EPOCH_DONATIONS_SEED, &writing_account);
+    let later = 100 + EPOCH_DONATIONS_LEN as u64;
+
+    donate_in(100, &mut writing_account, &mut epoch_donations_account, 300);
+    // This epoch lands in the same slot, it drops the old one instead of adding to it.
+    donate_in(later, &mut writing_account, &mut epoch_donations_account, 7);
+
+    assert_eq!(donated_in(&epoch_donations_account, 100), 0);
+    assert_eq!(donated_in(&epoch_donations_account, later), 7);
+}
+
+#[test]
+fn tracking_is_optional_but_needs_the_system_program() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut epoch_donations_account = campaign_pda(EPOCH_DONATIONS_SEED, &writing_account);
+    let mut donator = TestAccount::wallet(1_000_000_000).signer();
+
+    donate(&mut writing_account, &mut donator, 100).unwrap();
+    assert_eq!(
+        donate_with(
+            &mut writing_account,
+            &mut donator,
+            100,
+            &mut [&mut epoch_donations_account],
+        ),
+        Err(ProgramError::NotEnoughAccountKeys)
+    );
+}
@@ -0,0 +1,119 @@
+use solana_program::{program_error::ProgramError, rent::Rent};
+
+use super::{
+    campaign, campaign_account, donate, instruction_data, process, read_campaign, set_clock,
+    system_program_account, TestAccount,
+};
+use crate::{
+    constants::{CLOCK_TOLERANCE_SECS, MAX_FINALIZE_BATCH},
+    CampaignDetails, CampaignStatus, CrowdfundingError,
+};
+
+const FINALIZE_EXPIRED: u8 = 18;
+const DEADLINE: i64 = 2_000_000;
+const AFTER_DEADLINE: i64 = DEADLINE + CLOCK_TOLERANCE_SECS + 1;
+
+fn expired(goal: u64, amount_donated: u64) -> CampaignDetails {
+    let mut details = campaign(&TestAccount::wallet(0));
+    details.deadline = DEADLINE;
+    details.goal = goal;
+    details.amount_donated = amount_donated;
+    details
+}
+
+fn finalize(keeper: &mut TestAccount, campaigns: &mut [TestAccount]) -> Result<(), ProgramError> {
+    let mut system_program = system_program_account();
+    let mut accounts = vec![keeper, &mut system_program];
+    accounts.extend(campaigns.iter_mut());
+    process(&mut accounts, &instruction_data(FINALIZE_EXPIRED, ()))
+}
+
+fn final_status(writing_account: &TestAccount) -> Option<CampaignStatus> {
+    read_campaign(writing_account).final_status
+}
+
+#[test]
+fn finalizes_a_batch_and_skips_the_finalized_ones() {
+    let mut keeper = TestAccount::wallet(1_000_000).signer();
+    // Finalized earlier with another status, it must keep it.
+    let mut already_finalized = expired(1_000, 10);
+    already_finalized.final_status = Some(CampaignStatus::Succeeded);
+    let mut campaigns = [
+        campaign_account(expired(1_000, 1_000), 1_000),
+        campaign_account(already_finalized, 10),
+        campaign_account(expired(1_000, 10), 10),
+        campaign_account(expired(0, 0), 0),
+    ];
+    let finalized_len = campaigns[1].data().len();
+
+    set_clock(AFTER_DEADLINE, 0);
+    finalize(&mut keeper, &mut campaigns).unwrap();
+
+    assert_eq!(final_status(&campaigns[0]), Some(CampaignStatus::Succeeded));
+    assert_eq!(final_status(&campaigns[1]), Some(CampaignStatus::Succeeded));
+    assert_eq!(final_status(&campaigns[2]), Some(CampaignStatus::Failed));
+    assert_eq!(final_status(&campaigns[3]), Some(CampaignStatus::Ended));
+
+    // The keeper paid for the byte the status adds to the three newly finalized campaigns.
+    let rent = Rent::default();
+    let extra_rent = rent.minimum_balance(finalized_len) - rent.minimum_balance(finalized_len - 1);
+    assert_eq!(keeper.lamports, 1_000_000 - 3 * extra_rent);
+    for writing_account in &campaigns {
+        assert_eq!(writing_account.data().len(), finalized_len);
+    }
+}
+
+#[test]
+fn rejects_a_campaign_that_has_not_ended() {
+    let mut keeper = TestAccount::wallet(1_000_000).signer();
+    let mut campaigns = [campaign_account(expired(1_000, 10), 10)];
+
+    set_clock(DEADLINE + CLOCK_TOLERANCE_SECS, 0);
+    assert_eq!(
+        finalize(&mut keeper, &mut campaigns),
+        Err(CrowdfundingError::CampaignNotExpired.into())
+    );
+    assert_eq!(final_status(&campaigns[0]), None);
+}
+
+#[test]
+fn the_keeper_signs() {
+    let mut keeper = TestAccount::wallet(1_000_000);
+    let mut campaigns = [campaign_account(expired(0, 0), 0)];
+
+    set_clock(AFTER_DEADLINE, 0);
+    assert_eq!(
+        finalize(&mut keeper, &mut campaigns),
+        Err(ProgramError::IncorrectProgramId)
+    );
+}
+
+#[test]
+fn caps_the_batch_size() {
+    let mut keeper = TestAccount::wallet(1_000_000).signer();
+    let mut campaigns: Vec<TestAccount> = (0..=MAX_FINALIZE_BATCH)
+        .map(|_| campaign_account(expired(0, 0), 0))
+        .collect();
+
+    set_clock(AFTER_DEADLINE, 0);
+    assert_eq!(
+        finalize(&mut keeper, &mut campaigns),
+        Err(ProgramError::InvalidArgument)
+    );
+    finalize(&mut keeper, &mut campaigns[1..]).unwrap();
+}
+
+#[test]
+fn a_finalized_campaign_takes_no_donations() {
+    let mut keeper = TestAccount::wallet(1_000_000).signer();
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut campaigns = [campaign_account(expired(1_000, 10), 10)];
+    set_clock(AFTER_DEADLINE, 0);
+    finalize(&mut keeper, &mut campaigns).unwrap();
+
+    assert_eq!(
+        donate(&mut campaigns[0], &mut donator, 100),
+        Err(CrowdfundingError::CampaignFinalized.into())
+    );
+    assert_eq!(read_campaign(&campaigns[0]).amount_donated, 10);
+}
@@ -0,0 +1,178 @@
+use borsh::BorshSerialize;
+use solana_program::{program_error::ProgramError, rent::Rent};
+
+use super::{
+    balance, campaign, campaign_account, instruction_data, process, read_campaign, TestAccount,
+    PROGRAM_ID,
+};
+use crate::{CampaignDetails, ForkCampaignRequest};
+
+const FORK_CAMPAIGN: u8 = 5;
+
+// A campaign with 1 000 lamports donated and held.
+fn parent(admin: &TestAccount) -> TestAccount {
+    let mut details = campaign(admin);
+    details.amount_donated = 1_000;
+    details.goal = 5_000;
+    campaign_account(details, 1_000)
+}
+
+fn child(admin: &TestAccount) -> CampaignDetails {
+    let mut child = campaign(admin);
+    child.name = "Child".to_string();
+    child.goal = 300;
+    child
+}
+
+// An empty account of the right size for `child`, with its rent paid.
+fn empty_account(child: &CampaignDetails) -> TestAccount {
+    let space = child.try_to_vec().unwrap().len();
+    TestAccount::new(
+        PROGRAM_ID,
+        Rent::default().minimum_balance(space),
+        &vec![0; space],
+    )
+}
+
+// Forks `amount` lamports of `writing_account` into a new child campaign of the admin.
+fn fork(
+    writing_account: &mut TestAccount,
+    child_account: &mut TestAccount,
+    admin: &mut TestAccount,
+    amount: u64,
+    child: CampaignDetails,
+) -> Result<(), ProgramError> {
+    process(
+        &mut [writing_account, child_account, admin],
+        &instruction_data(FORK_CAMPAIGN, ForkCampaignRequest { amount, child }),
+    )
+}
+
+#[test]
+fn moves_a_portion_to_the_child() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = parent(&admin);
+    let child = child(&admin);
+    let mut child_account = empty_account(&child);
+
+    fork(
+        &mut writing_account,
+        &mut child_account,
+        &mut admin,
+        400,
+        child,
+    )
+    .unwrap();
+
+    let parent = read_campaign(&writing_account);
+    assert_eq!(parent.amount_donated, 600);
+    assert_eq!(parent.goal, 5_000);
+    assert_eq!(balance(&writing_account), 600);
+
+    let child = read_campaign(&child_account);
+    assert_eq!(child.name, "Child");
+    assert_eq!(child.admin, admin.key);
+    assert_eq!(child.amount_donated, 400);
+    assert_eq!(child.goal, 300);
+    assert_eq!(balance(&child_account), 400);
+}
+
+#[test]
+fn can_fork_everything_donated() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = parent(&admin);
+    let child = child(&admin);
+    let mut child_account = empty_account(&child);
+
+    fork(
+        &mut writing_account,
+        &mut child_account,
+        &mut admin,
+        1_000,
+        child,
+    )
+    .unwrap();
+
+    assert_eq!(read_campaign(&writing_account).amount_donated, 0);
+    assert_eq!(balance(&writing_account), 0);
+    assert_eq!(read_campaign(&child_account).amount_donated, 1_000);
+}
+
+#[test]
+fn rejects_more_than_donated_or_nothing() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = parent(&admin);
+    let mut child_account = empty_account(&child(&admin));
+
+    let too_much = child(&admin);
+    assert_eq!(
+        fork(
+            &mut writing_account,
+            &mut child_account,
+            &mut admin,
+            1_001,
+            too_much
+        ),
+        Err(ProgramError::InsufficientFunds)
+    );
+    let nothing = child(&admin);
+    assert_eq!(
+        fork(
+            &mut writing_account,
+            &mut child_account,
+            &mut admin,
+            0,
+            nothing
+        ),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    assert_eq!(read_campaign(&writing_account).amount_donated, 1_000);
+    assert_eq!(balance(&writing_account), 1_000);
+}
+
+#[test]
+fn only_the_admin_forks_into_its_own_child() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut other = TestAccount::wallet(0).signer();
+    let mut writing_account = parent(&admin);
+
+    let child = child(&admin);
+    let mut child_account = empty_account(&child);
+    assert_eq!(
+        fork(
+            &mut writing_account,
+            &mut child_account,
+            &mut other,
+            400,
+            child
+        ),
+        Err(ProgramError::InvalidAccountData)
+    );
+
+    let mut others_child = campaign(&other);
+    others_child.goal = 300;
+    let mut child_account = empty_account(&others_child);
+    assert_eq!(
+        fork(
+            &mut writing_account,
+            &mut child_account,
+            &mut admin,
+            400,
+            others_child
+        ),
+        Err(ProgramError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn never_writes_over_an_existing_campaign() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = parent(&admin);
+    let mut existing = campaign_account(child(&admin), 0);
+
+    let child = child(&admin);
+    assert_eq!(
+        fork(&mut writing_account, &mut existing, &mut admin, 400, child),
+        Err(ProgramError::AccountAlreadyInitialized)
+    );
+}
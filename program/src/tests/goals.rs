@@ -0,0 +1,111 @@
+use solana_program::program_error::ProgramError;
+
+use super::{
+    campaign, campaign_account, create_campaign, donate, instruction_data, logs, process,
+    read_campaign, return_data, TestAccount,
+};
+use crate::constants::{MAX_GOAL, MIN_INITIAL_FUNDING, NO_GOAL_SHORTFALL};
+
+const SHORTFALL: u8 = 19;
+
+fn reached(tier: &str) -> usize {
+    logs().iter().filter(|log| log.starts_with(tier)).count()
+}
+
+#[test]
+fn logs_each_tier_once_when_crossed() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.goal = 1_000;
+    details.stretch_goal = 2_000;
+    let mut writing_account = create_campaign(&mut admin, &details, MIN_INITIAL_FUNDING).unwrap();
+
+    donate(&mut writing_account, &mut donator, 999).unwrap();
+    assert_eq!(reached("CF:GOAL_REACHED"), 0);
+
+    donate(&mut writing_account, &mut donator, 1).unwrap();
+    assert_eq!(logs().last().unwrap(), "CF:GOAL_REACHED 1000");
+
+    donate(&mut writing_account, &mut donator, 500).unwrap();
+    assert_eq!(reached("CF:GOAL_REACHED"), 1);
+    assert_eq!(reached("CF:STRETCH_GOAL_REACHED"), 0);
+
+    donate(&mut writing_account, &mut donator, 600).unwrap();
+    assert_eq!(logs().last().unwrap(), "CF:STRETCH_GOAL_REACHED 2000");
+
+    donate(&mut writing_account, &mut donator, 1).unwrap();
+    assert_eq!(reached("CF:GOAL_REACHED"), 1);
+    assert_eq!(reached("CF:STRETCH_GOAL_REACHED"), 1);
+    assert_eq!(read_campaign(&writing_account).amount_donated, 2_101);
+}
+
+#[test]
+fn one_donation_can_cross_both_tiers() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.goal = 1_000;
+    details.stretch_goal = 2_000;
+    let mut writing_account = create_campaign(&mut admin, &details, MIN_INITIAL_FUNDING).unwrap();
+
+    donate(&mut writing_account, &mut donator, 5_000).unwrap();
+
+    assert_eq!(reached("CF:GOAL_REACHED 1000"), 1);
+    assert_eq!(reached("CF:STRETCH_GOAL_REACHED 2000"), 1);
+}
+
+#[test]
+fn stretch_goal_must_be_above_the_goal() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+
+    for (goal, stretch_goal) in [(1_000, 1_000), (1_000, 999), (0, 1_000)] {
+        details.goal = goal;
+        details.stretch_goal = stretch_goal;
+        assert_eq!(
+            create_campaign(&mut admin, &details, MIN_INITIAL_FUNDING).err(),
+            Some(ProgramError::InvalidInstructionData),
+            "goal {} stretch_goal {}",
+            goal,
+            stretch_goal
+        );
+    }
+
+    details.goal = 1_000;
+    details.stretch_goal = MAX_GOAL + 1;
+    assert_eq!(
+        create_campaign(&mut admin, &details, MIN_INITIAL_FUNDING).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+
+    details.stretch_goal = 1_001;
+    assert!(create_campaign(&mut admin, &details, MIN_INITIAL_FUNDING).is_ok());
+}
+
+fn shortfall(goal: u64, amount_donated: u64) -> u64 {
+    let mut details = campaign(&TestAccount::wallet(0));
+    details.goal = goal;
+    details.amount_donated = amount_donated;
+    let mut writing_account = campaign_account(details, amount_donated);
+    process(
+        &mut [&mut writing_account],
+        &instruction_data(SHORTFALL, ()),
+    )
+    .unwrap();
+    u64::from_le_bytes(return_data().unwrap().try_into().unwrap())
+}
+
+#[test]
+fn the_shortfall_is_what_is_left_to_the_goal() {
+    assert_eq!(shortfall(1_000, 0), 1_000);
+    assert_eq!(shortfall(1_000, 999), 1);
+    assert_eq!(shortfall(1_000, 1_000), 0);
+    assert_eq!(shortfall(1_000, 1_500), 0);
+}
+
+#[test]
+fn a_campaign_without_goal_returns_the_sentinel() {
+    assert_eq!(shortfall(0, 0), NO_GOAL_SHORTFALL);
+    assert_eq!(shortfall(0, 1_500), NO_GOAL_SHORTFALL);
+}
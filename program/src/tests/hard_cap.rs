@@ -0,0 +1,148 @@
+use solana_program::program_error::ProgramError;
+
+use super::{
+    campaign, campaign_account, create_campaign, donate, instruction_data, process, read_campaign,
+    TestAccount,
+};
+use crate::{constants::MAX_GOAL, CrowdfundingError};
+
+const RAISE_HARD_CAP: u8 = 4;
+
+fn raise(
+    writing_account: &mut TestAccount,
+    admin: &mut TestAccount,
+    new_cap: u64,
+) -> Result<(), ProgramError> {
+    process(
+        &mut [writing_account, admin],
+        &instruction_data(RAISE_HARD_CAP, new_cap),
+    )
+}
+
+fn capped_campaign(admin: &TestAccount, hard_cap: u64, amount_donated: u64) -> TestAccount {
+    let mut details = campaign(admin);
+    details.hard_cap = hard_cap;
+    details.amount_donated = amount_donated;
+    campaign_account(details, amount_donated)
+}
+
+#[test]
+fn raising_the_cap_lets_more_donations_in() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = capped_campaign(&admin, 1_000, 1_000);
+
+    assert_eq!(
+        donate(&mut writing_account, &mut donator, 1),
+        Err(ProgramError::InvalidArgument)
+    );
+
+    raise(&mut writing_account, &mut admin, 1_500).unwrap();
+    assert_eq!(read_campaign(&writing_account).hard_cap, 1_500);
+
+    donate(&mut writing_account, &mut donator, 500).unwrap();
+    assert_eq!(read_campaign(&writing_account).amount_donated, 1_500);
+}
+
+#[test]
+fn the_cap_can_only_go_up() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = capped_campaign(&admin, 1_000, 400);
+
+    for new_cap in [999, 1_000, 0] {
+        assert_eq!(
+            raise(&mut writing_account, &mut admin, new_cap),
+            Err(ProgramError::InvalidInstructionData),
+            "new_cap {}",
+            new_cap
+        );
+    }
+    assert_eq!(read_campaign(&writing_account).hard_cap, 1_000);
+}
+
+#[test]
+fn the_cap_stays_under_max_goal() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = capped_campaign(&admin, 1_000, 0);
+
+    assert_eq!(
+        raise(&mut writing_account, &mut admin, MAX_GOAL + 1),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    raise(&mut writing_account, &mut admin, MAX_GOAL).unwrap();
+}
+
+#[test]
+fn an_uncapped_campaign_has_nothing_to_raise() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = capped_campaign(&admin, 0, 0);
+
+    assert_eq!(
+        raise(&mut writing_account, &mut admin, 1_000),
+        Err(ProgramError::InvalidArgument)
+    );
+}
+
+#[test]
+fn only_the_admin_raises_the_cap_of_a_mutable_campaign() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut other = TestAccount::wallet(0).signer();
+    let mut writing_account = capped_campaign(&admin, 1_000, 0);
+    assert_eq!(
+        raise(&mut writing_account, &mut other, 2_000),
+        Err(ProgramError::InvalidAccountData)
+    );
+
+    let mut details = read_campaign(&writing_account);
+    details.immutable = true;
+    let mut writing_account = campaign_account(details, 0);
+    assert_eq!(
+        raise(&mut writing_account, &mut admin, 2_000),
+        Err(CrowdfundingError::CampaignImmutable.into())
+    );
+}
+
+fn limited_campaign(admin: &TestAccount, max_per_tx: Option<u64>) -> TestAccount {
+    let mut details = campaign(admin);
+    details.max_per_tx = max_per_tx;
+    campaign_account(details, 0)
+}
+
+#[test]
+fn donations_up_to_max_per_tx_go_through() {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = limited_campaign(&admin, Some(1_000));
+
+    assert_eq!(
+        donate(&mut writing_account, &mut donator, 1_001),
+        Err(CrowdfundingError::DonationTooLarge.into())
+    );
+    assert_eq!(read_campaign(&writing_account).amount_donated, 0);
+
+    // It limits a single donation, not the total.
+    donate(&mut writing_account, &mut donator, 1_000).unwrap();
+    donate(&mut writing_account, &mut donator, 1_000).unwrap();
+    assert_eq!(read_campaign(&writing_account).amount_donated, 2_000);
+}
+
+#[test]
+fn no_max_per_tx_means_no_limit() {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = limited_campaign(&admin, None);
+
+    donate(&mut writing_account, &mut donator, MAX_GOAL).unwrap();
+}
+
+#[test]
+fn max_per_tx_can_not_be_zero() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.max_per_tx = Some(0);
+
+    assert_eq!(
+        create_campaign(&mut admin, &details, 0).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+}
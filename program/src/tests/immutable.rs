@@ -0,0 +1,104 @@
+use borsh::BorshSerialize;
+
+use super::{
+    balance, campaign, create_campaign, donate, instruction_data, now, process, read_campaign,
+    system_program_account, TestAccount, PROGRAM_ID,
+};
+use crate::{
+    constants::MIN_INITIAL_FUNDING, CrowdfundingError, ForkCampaignRequest, MetadataHashRequest,
+    ThankYouRequest,
+};
+
+// An immutable campaign created through CreateCampaign, with a cap and a deadline to edit.
+fn immutable_campaign(admin: &mut TestAccount) -> TestAccount {
+    let mut details = campaign(admin);
+    details.immutable = true;
+    details.hard_cap = 10_000;
+    details.deadline = now() + 1_000;
+    let writing_account = create_campaign(admin, &details, MIN_INITIAL_FUNDING).unwrap();
+    assert!(read_campaign(&writing_account).immutable);
+    writing_account
+}
+
+#[test]
+fn rejects_every_edit() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let mut writing_account = immutable_campaign(&mut admin);
+    let mut system = system_program_account();
+    let data = writing_account.data().to_vec();
+
+    let edits = [
+        instruction_data(4, 20_000u64),
+        instruction_data(
+            12,
+            ThankYouRequest {
+                thank_you: "Thanks".to_string(),
+            },
+        ),
+        instruction_data(
+            21,
+            MetadataHashRequest {
+                metadata_hash: [1; 32],
+            },
+        ),
+        instruction_data(27, ()),
+    ];
+    for edit in edits {
+        assert_eq!(
+            process(&mut [&mut writing_account, &mut admin, &mut system], &edit),
+            Err(CrowdfundingError::CampaignImmutable.into()),
+            "tag {}",
+            edit[0]
+        );
+    }
+
+    // Forking would move the donations into a child with new terms, it takes the child
+    // before the admin.
+    let fork = ForkCampaignRequest {
+        amount: 1,
+        child: campaign(&admin),
+    };
+    let child_len = fork.child.try_to_vec().unwrap().len();
+    let mut child = TestAccount::new(PROGRAM_ID, 1_000_000_000, &vec![0; child_len]);
+    assert_eq!(
+        process(
+            &mut [&mut writing_account, &mut child, &mut admin],
+            &instruction_data(5, &fork)
+        ),
+        Err(CrowdfundingError::CampaignImmutable.into())
+    );
+    assert_eq!(writing_account.data(), &data[..]);
+}
+
+#[test]
+fn donations_and_withdrawals_still_work() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = immutable_campaign(&mut admin);
+
+    donate(&mut writing_account, &mut donator, 500).unwrap();
+    process(
+        &mut [&mut writing_account, &mut admin],
+        &instruction_data(1, 200u64),
+    )
+    .unwrap();
+
+    assert_eq!(read_campaign(&writing_account).amount_donated, 500);
+    assert_eq!(balance(&writing_account), MIN_INITIAL_FUNDING + 300);
+    assert_eq!(admin.lamports, 200);
+}
+
+#[test]
+fn a_mutable_campaign_can_be_edited() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.hard_cap = 10_000;
+    let mut writing_account = create_campaign(&mut admin, &details, MIN_INITIAL_FUNDING).unwrap();
+
+    process(
+        &mut [&mut writing_account, &mut admin],
+        &instruction_data(4, 20_000u64),
+    )
+    .unwrap();
+    assert_eq!(read_campaign(&writing_account).hard_cap, 20_000);
+}
@@ -0,0 +1,256 @@
+use borsh::BorshSerialize;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use super::{
+    campaign, campaign_account, instruction_data, process, read_campaign, TestAccount, PROGRAM_ID,
+};
+use crate::{
+    constants::{INSTRUCTION_FORMAT_VERSION, VERSIONED_INSTRUCTION_MARKER},
+    CreateAndDonateRequest, CreateVoucherRequest, CrowdfundingError, CrowdfundingInstruction,
+    DirectoryPageRequest, DonorRequest, ForkCampaignRequest, MetadataHashRequest,
+    RaiseHardCapRequest, ThankYouRequest, VoucherRequest, WithdrawRequest, WithdrawWithNoteRequest,
+};
+
+fn decode(data: &[u8]) -> Result<CrowdfundingInstruction, ProgramError> {
+    CrowdfundingInstruction::try_from(data)
+}
+
+fn decoded(data: &[u8]) -> String {
+    format!("{:?}", decode(data).unwrap())
+}
+
+const INVALID_INSTRUCTION: ProgramError =
+    ProgramError::Custom(CrowdfundingError::InvalidInstruction as u32);
+
+#[test]
+fn decodes_every_instruction_without_payload() {
+    let tags = [
+        (2, "Donate"),
+        (3, "MigrateV0ToV1"),
+        (6, "RecentDonors"),
+        (10, "Status"),
+        (11, "Prune"),
+        (13, "GetThankYou"),
+        (14, "DonationHistogram"),
+        (18, "FinalizeExpired"),
+        (19, "Shortfall"),
+        (20, "Reconcile"),
+        (22, "RegisterCampaign"),
+        (25, "DonateWithReferrer"),
+        (26, "ActivityScore"),
+        (27, "EndNow"),
+    ];
+    for (tag, name) in tags {
+        assert_eq!(decoded(&[tag]), name);
+        // They don't take trailing bytes either.
+        assert_eq!(
+            decode(&[tag, 0]).err(),
+            Some(INVALID_INSTRUCTION),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn decodes_every_instruction_with_a_payload() {
+    let donor = Pubkey::new_unique();
+    let payloads = [
+        (
+            1,
+            WithdrawRequest { amount: 5 }.try_to_vec(),
+            "Withdraw(WithdrawRequest { amount: 5 })".to_string(),
+        ),
+        (
+            4,
+            RaiseHardCapRequest { new_cap: 9 }.try_to_vec(),
+            "RaiseHardCap(RaiseHardCapRequest { new_cap: 9 })".to_string(),
+        ),
+        (
+            7,
+            WithdrawRequest { amount: 6 }.try_to_vec(),
+            "WithdrawOrMax(WithdrawRequest { amount: 6 })".to_string(),
+        ),
+        (
+            8,
+            DonorRequest { donor }.try_to_vec(),
+            format!("BlockDonor(DonorRequest {{ donor: {} }})", donor),
+        ),
+        (
+            9,
+            DonorRequest { donor }.try_to_vec(),
+            format!("UnblockDonor(DonorRequest {{ donor: {} }})", donor),
+        ),
+        (
+            12,
+            ThankYouRequest {
+                thank_you: "Thanks".to_string(),
+            }
+            .try_to_vec(),
+            "SetThankYou(ThankYouRequest { thank_you: \"Thanks\" })".to_string(),
+        ),
+        (
+            15,
+            CreateVoucherRequest {
+                voucher_id: 1,
+                amount: 2,
+            }
+            .try_to_vec(),
+            "CreateVoucher(CreateVoucherRequest { voucher_id: 1, amount: 2 })".to_string(),
+        ),
+        (
+            16,
+            VoucherRequest { voucher_id: 3 }.try_to_vec(),
+            "RedeemVoucher(VoucherRequest { voucher_id: 3 })".to_string(),
+        ),
+        (
+            17,
+            CreateAndDonateRequest { amount: 4 }.try_to_vec(),
+            "CreateAndDonate(CreateAndDonateRequest { amount: 4 })".to_string(),
+        ),
+        (
+            21,
+            MetadataHashRequest {
+                metadata_hash: [1; 32],
+            }
+            .try_to_vec(),
+            format!(
+                "SetMetadataHash(MetadataHashRequest {{ metadata_hash: {:?} }})",
+                [1u8; 32]
+            ),
+        ),
+        (
+            23,
+            DirectoryPageRequest { page: 2 }.try_to_vec(),
+            "DirectoryPage(DirectoryPageRequest { page: 2 })".to_string(),
+        ),
+        (
+            24,
+            WithdrawWithNoteRequest {
+                amount: 7,
+                note: "Rent".to_string(),
+            }
+            .try_to_vec(),
+            "WithdrawWithNote(WithdrawWithNoteRequest { amount: 7, note: \"Rent\" })".to_string(),
+        ),
+        (
+            28,
+            VoucherRequest { voucher_id: 8 }.try_to_vec(),
+            "CloseVoucher(VoucherRequest { voucher_id: 8 })".to_string(),
+        ),
+    ];
+    for (tag, payload, expected) in payloads {
+        let mut data = vec![tag];
+        data.extend(payload.unwrap());
+        assert_eq!(decoded(&data), expected);
+
+        // A payload cut short or with trailing bytes is rejected.
+        assert_eq!(
+            decode(&data[..data.len() - 1]).err(),
+            Some(INVALID_INSTRUCTION),
+            "{}",
+            expected
+        );
+        data.push(0);
+        assert_eq!(
+            decode(&data).err(),
+            Some(INVALID_INSTRUCTION),
+            "{}",
+            expected
+        );
+    }
+}
+
+#[test]
+fn decodes_the_campaign_payloads() {
+    let admin = TestAccount::wallet(0);
+    let details = campaign(&admin);
+
+    match decode(&instruction_data(0, &details)).unwrap() {
+        CrowdfundingInstruction::CreateCampaign(decoded) => assert_eq!(decoded.admin, admin.key),
+        other => panic!("decoded {:?}", other),
+    }
+
+    let fork = ForkCampaignRequest {
+        amount: 10,
+        child: details,
+    };
+    match decode(&instruction_data(5, &fork)).unwrap() {
+        CrowdfundingInstruction::ForkCampaign(decoded) => {
+            assert_eq!(decoded.amount, 10);
+            assert_eq!(decoded.child.admin, admin.key);
+        }
+        other => panic!("decoded {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_unknown_tags_and_empty_data() {
+    for tag in [29, 100, u8::MAX - 1] {
+        assert_eq!(
+            decode(&[tag]).err(),
+            Some(INVALID_INSTRUCTION),
+            "tag {}",
+            tag
+        );
+    }
+    assert_eq!(decode(&[]).err(), Some(INVALID_INSTRUCTION));
+}
+
+#[test]
+fn strips_the_format_header() {
+    assert_eq!(
+        decoded(&[VERSIONED_INSTRUCTION_MARKER, INSTRUCTION_FORMAT_VERSION, 2]),
+        "Donate"
+    );
+    assert_eq!(
+        decode(&[
+            VERSIONED_INSTRUCTION_MARKER,
+            INSTRUCTION_FORMAT_VERSION + 1,
+            2
+        ])
+        .err(),
+        Some(CrowdfundingError::UnsupportedInstructionVersion.into())
+    );
+    assert_eq!(
+        decode(&[VERSIONED_INSTRUCTION_MARKER]).err(),
+        Some(INVALID_INSTRUCTION)
+    );
+}
+
+// Donates 100 lamports with `header` in front of the Donate tag.
+fn donate_with_header(header: &[u8]) -> Result<u64, ProgramError> {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut donator_program_account = TestAccount::new(PROGRAM_ID, 100, &[]);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut data = header.to_vec();
+    data.push(2);
+
+    process(
+        &mut [
+            &mut writing_account,
+            &mut donator_program_account,
+            &mut donator,
+        ],
+        &data,
+    )?;
+    Ok(read_campaign(&writing_account).amount_donated)
+}
+
+#[test]
+fn processes_the_current_format_and_the_unversioned_one() {
+    assert_eq!(
+        donate_with_header(&[VERSIONED_INSTRUCTION_MARKER, INSTRUCTION_FORMAT_VERSION]),
+        Ok(100)
+    );
+    assert_eq!(donate_with_header(&[]), Ok(100));
+}
+
+#[test]
+fn rejects_a_future_format() {
+    assert_eq!(
+        donate_with_header(&[VERSIONED_INSTRUCTION_MARKER, INSTRUCTION_FORMAT_VERSION + 1]),
+        Err(CrowdfundingError::UnsupportedInstructionVersion.into())
+    );
+}
@@ -0,0 +1,62 @@
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use super::{campaign, campaign_account, donate, TestAccount};
+
+// Donates to a campaign whose data went through `corrupt`, and checks nothing was credited.
+fn donate_to_corrupted(corrupt: impl FnOnce(&mut TestAccount)) -> ProgramError {
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    corrupt(&mut writing_account);
+    let data = writing_account.data().to_vec();
+    let lamports = writing_account.lamports;
+
+    let error = donate(&mut writing_account, &mut donator, 100).unwrap_err();
+    assert_eq!(writing_account.data(), &data[..]);
+    assert_eq!(writing_account.lamports, lamports);
+    error
+}
+
+#[test]
+fn rejects_a_truncated_campaign() {
+    let error = donate_to_corrupted(|account| {
+        let data = account.data().to_vec();
+        account.set_data(&data[..data.len() - 8]);
+    });
+    assert_eq!(error, ProgramError::InvalidAccountData);
+}
+
+#[test]
+fn rejects_an_uninitialized_account() {
+    let error = donate_to_corrupted(|account| {
+        let len = account.data().len();
+        account.set_data(&vec![0; len]);
+    });
+    assert_eq!(error, ProgramError::InvalidAccountData);
+}
+
+#[test]
+fn rejects_another_version() {
+    let error = donate_to_corrupted(|account| {
+        let mut data = account.data().to_vec();
+        data[0] += 1;
+        account.set_data(&data);
+    });
+    assert_eq!(error, ProgramError::InvalidAccountData);
+}
+
+#[test]
+fn rejects_a_campaign_without_admin() {
+    let error = donate_to_corrupted(|account| {
+        let mut data = account.data().to_vec();
+        data[1..33].fill(0);
+        account.set_data(&data);
+    });
+    assert_eq!(error, ProgramError::UninitializedAccount);
+}
+
+#[test]
+fn rejects_an_account_owned_by_another_program() {
+    let error = donate_to_corrupted(|account| account.owner = Pubkey::new_unique());
+    assert_eq!(error, ProgramError::IncorrectProgramId);
+}
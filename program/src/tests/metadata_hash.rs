@@ -0,0 +1,65 @@
+use solana_program::program_error::ProgramError;
+
+use super::{campaign, campaign_account, instruction_data, process, read_campaign, TestAccount};
+use crate::MetadataHashRequest;
+
+const SET_METADATA_HASH: u8 = 21;
+
+fn set_metadata_hash(
+    writing_account: &mut TestAccount,
+    admin: &mut TestAccount,
+    metadata_hash: [u8; 32],
+) -> Result<(), ProgramError> {
+    process(
+        &mut [writing_account, admin],
+        &instruction_data(SET_METADATA_HASH, MetadataHashRequest { metadata_hash }),
+    )
+}
+
+#[test]
+fn the_admin_updates_the_hash() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.metadata_hash = [1; 32];
+    let mut writing_account = campaign_account(details, 0);
+    let len = writing_account.data().len();
+
+    set_metadata_hash(&mut writing_account, &mut admin, [2; 32]).unwrap();
+    assert_eq!(read_campaign(&writing_account).metadata_hash, [2; 32]);
+    assert_eq!(writing_account.data().len(), len);
+
+    set_metadata_hash(&mut writing_account, &mut admin, [3; 32]).unwrap();
+    assert_eq!(read_campaign(&writing_account).metadata_hash, [3; 32]);
+}
+
+#[test]
+fn rejects_an_all_zeros_hash() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.metadata_hash = [1; 32];
+    let mut writing_account = campaign_account(details, 0);
+
+    assert_eq!(
+        set_metadata_hash(&mut writing_account, &mut admin, [0; 32]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    assert_eq!(read_campaign(&writing_account).metadata_hash, [1; 32]);
+}
+
+#[test]
+fn only_the_signing_admin_updates_the_hash() {
+    let admin = TestAccount::wallet(0).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+
+    let mut other = TestAccount::wallet(0).signer();
+    assert_eq!(
+        set_metadata_hash(&mut writing_account, &mut other, [2; 32]),
+        Err(ProgramError::InvalidAccountData)
+    );
+    let mut not_signing = TestAccount::wallet(0).with_key(admin.key);
+    assert_eq!(
+        set_metadata_hash(&mut writing_account, &mut not_signing, [2; 32]),
+        Err(ProgramError::IncorrectProgramId)
+    );
+    assert_eq!(read_campaign(&writing_account).metadata_hash, [0; 32]);
+}
@@ -0,0 +1,111 @@
+use borsh::BorshSerialize;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey, rent::Rent};
+
+use super::{
+    instruction_data, process, read_campaign, system_program_account, TestAccount, PROGRAM_ID,
+};
+use crate::constants::CAMPAIGN_VERSION;
+
+const MIGRATE_V0_TO_V1: u8 = 3;
+
+// The bytes a campaign had before versioning: admin, name, description, image_link and
+// amount_donated, nothing else.
+fn v0_bytes(admin: &Pubkey, amount_donated: u64) -> Vec<u8> {
+    (
+        *admin,
+        "Old".to_string(),
+        "From before versioning".to_string(),
+        "https://example.com/old.png".to_string(),
+        amount_donated,
+    )
+        .try_to_vec()
+        .unwrap()
+}
+
+fn migrate(campaign: &mut TestAccount, admin: &mut TestAccount) -> Result<(), ProgramError> {
+    let mut system = system_program_account();
+    process(
+        &mut [campaign, admin, &mut system],
+        &instruction_data(MIGRATE_V0_TO_V1, ()),
+    )
+}
+
+#[test]
+fn migrates_a_v0_campaign() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let data = v0_bytes(&admin.key, 500);
+    let rent = Rent::default().minimum_balance(data.len());
+    let mut campaign = TestAccount::new(PROGRAM_ID, rent + 500, &data);
+
+    migrate(&mut campaign, &mut admin).unwrap();
+
+    let migrated = read_campaign(&campaign);
+    assert_eq!(migrated.version, CAMPAIGN_VERSION);
+    assert_eq!(migrated.admin, admin.key);
+    assert_eq!(migrated.name, "Old");
+    assert_eq!(migrated.description, "From before versioning");
+    assert_eq!(migrated.image_link, "https://example.com/old.png");
+    assert_eq!(migrated.amount_donated, 500);
+    assert_eq!(migrated.goal, 0);
+    assert_eq!(migrated.deadline, 0);
+    // The admin paid the rent of the bigger account, the donations are all still there.
+    assert_eq!(
+        campaign.lamports,
+        Rent::default().minimum_balance(campaign.data().len()) + 500
+    );
+}
+
+#[test]
+fn a_second_migration_fails() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let data = v0_bytes(&admin.key, 0);
+    let rent = Rent::default().minimum_balance(data.len());
+    let mut campaign = TestAccount::new(PROGRAM_ID, rent, &data);
+
+    migrate(&mut campaign, &mut admin).unwrap();
+    let migrated = campaign.data().to_vec();
+
+    assert_eq!(
+        migrate(&mut campaign, &mut admin),
+        Err(ProgramError::AccountAlreadyInitialized)
+    );
+    assert_eq!(campaign.data(), &migrated[..]);
+}
+
+#[test]
+fn rejects_bytes_that_are_not_a_v0_campaign() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let mut data = v0_bytes(&admin.key, 0);
+    data.push(0);
+    let mut campaign = TestAccount::new(PROGRAM_ID, 1_000_000_000, &data);
+
+    assert_eq!(
+        migrate(&mut campaign, &mut admin),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn only_the_v0_admin_can_migrate() {
+    let admin = TestAccount::wallet(0);
+    let mut other = TestAccount::wallet(1_000_000_000).signer();
+    let mut campaign = TestAccount::new(PROGRAM_ID, 1_000_000_000, &v0_bytes(&admin.key, 0));
+
+    assert_eq!(
+        migrate(&mut campaign, &mut other),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
+
+// v0 didn't count withdrawals, the donations missing from the balance were withdrawn.
+#[test]
+fn counts_the_missing_donations_as_withdrawn() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let data = v0_bytes(&admin.key, 500);
+    let rent = Rent::default().minimum_balance(data.len());
+    let mut campaign = TestAccount::new(PROGRAM_ID, rent + 200, &data);
+
+    migrate(&mut campaign, &mut admin).unwrap();
+
+    assert_eq!(read_campaign(&campaign).total_withdrawn, 300);
+}
@@ -0,0 +1,420 @@
+// Unit tests that run the handlers natively. The syscalls the program makes (clock, rent,
+// logs, return data and CPIs to the system program) are answered by `TestStubs`, and the
+// accounts live in `TestAccount`s laid out like the runtime lays them out, so `realloc`
+// and `assign` work on them like they do on chain.
+
+mod acl;
+mod activity;
+mod block;
+#[cfg(feature = "client")]
+mod client;
+mod constants;
+mod create;
+mod create_and_donate;
+mod deadline;
+mod directory;
+mod donate;
+mod epoch_donations;
+mod finalize;
+mod fork;
+mod goals;
+mod hard_cap;
+mod immutable;
+mod instruction;
+mod load;
+mod metadata_hash;
+mod migrate;
+mod prune;
+mod recent_donors;
+mod reconcile;
+mod referral;
+mod resize;
+mod status;
+mod thank_you;
+mod validator;
+mod vesting;
+mod voucher;
+mod withdraw;
+
+use std::{
+    cell::{Cell, RefCell},
+    sync::Once,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    program_utils::limited_deserialize,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::{SystemError, SystemInstruction},
+    system_program,
+};
+
+use crate::{constants::CAMPAIGN_VERSION, process_instruction, CampaignDetails};
+
+pub(crate) const PROGRAM_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+
+// Handles a CPI to a program other than the system program, see `on_cpi`.
+type CpiHandler = Box<dyn Fn(&Instruction, &[AccountInfo]) -> ProgramResult>;
+
+// The test harness runs every test on its own thread, so keeping the state of the stubs in
+// thread locals gives every test a fresh chain.
+thread_local! {
+    static CLOCK: Cell<(i64, u64)> = const { Cell::new((1_000_000, 100)) };
+    static LOGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static RETURN_DATA: RefCell<Option<(Pubkey, Vec<u8>)>> = const { RefCell::new(None) };
+    static CPI_HANDLER: RefCell<Option<CpiHandler>> = const { RefCell::new(None) };
+}
+
+struct TestStubs;
+
+impl SyscallStubs for TestStubs {
+    fn sol_log(&self, message: &str) {
+        LOGS.with(|logs| logs.borrow_mut().push(message.to_string()));
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let (unix_timestamp, epoch) = CLOCK.with(Cell::get);
+        let clock = Clock {
+            unix_timestamp,
+            epoch,
+            ..Clock::default()
+        };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        0
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        0
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        RETURN_DATA.with(|data| data.borrow().clone())
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        RETURN_DATA
+            .with(|return_data| *return_data.borrow_mut() = Some((PROGRAM_ID, data.to_vec())));
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let mut accounts = Vec::new();
+        for meta in &instruction.accounts {
+            let info = account_infos
+                .iter()
+                .find(|info| *info.key == meta.pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let signed_by_program = signers_seeds
+                .iter()
+                .any(|seeds| Pubkey::create_program_address(seeds, &PROGRAM_ID) == Ok(meta.pubkey));
+            if meta.is_signer && !info.is_signer && !signed_by_program {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if meta.is_writable && !info.is_writable {
+                return Err(ProgramError::InvalidArgument);
+            }
+            accounts.push(info.clone());
+        }
+
+        if instruction.program_id == system_program::id() {
+            run_system_instruction(&instruction.data, &accounts)
+        } else {
+            CPI_HANDLER.with(|handler| match &*handler.borrow() {
+                Some(handler) => handler(instruction, &accounts),
+                None => panic!("unexpected CPI to {}", instruction.program_id),
+            })
+        }
+    }
+}
+
+// The subset of the system program the campaign program calls.
+fn run_system_instruction(data: &[u8], accounts: &[AccountInfo]) -> ProgramResult {
+    let instruction: SystemInstruction =
+        limited_deserialize(data, 1232).map_err(|_| ProgramError::InvalidInstructionData)?;
+    match instruction {
+        SystemInstruction::Transfer { lamports } => transfer(&accounts[0], &accounts[1], lamports),
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            if accounts[1].lamports() != 0 {
+                return Err(ProgramError::Custom(
+                    SystemError::AccountAlreadyInUse as u32,
+                ));
+            }
+            transfer(&accounts[0], &accounts[1], lamports)?;
+            allocate(&accounts[1], space)?;
+            accounts[1].assign(&owner);
+            Ok(())
+        }
+        SystemInstruction::Allocate { space } => allocate(&accounts[0], space),
+        SystemInstruction::Assign { owner } => {
+            if *accounts[0].owner != system_program::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            accounts[0].assign(&owner);
+            Ok(())
+        }
+        other => panic!("unexpected system instruction {:?}", other),
+    }
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if *from.owner != system_program::id() || !from.data_is_empty() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if from.lamports() < lamports {
+        return Err(ProgramError::Custom(
+            SystemError::ResultWithNegativeLamports as u32,
+        ));
+    }
+    **from.try_borrow_mut_lamports()? -= lamports;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+fn allocate(account: &AccountInfo, space: u64) -> ProgramResult {
+    if *account.owner != system_program::id() || !account.data_is_empty() {
+        return Err(ProgramError::Custom(
+            SystemError::AccountAlreadyInUse as u32,
+        ));
+    }
+    account.realloc(space as usize, true)
+}
+
+// Installs the stubs, once for the whole test binary.
+pub(crate) fn install_stubs() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        set_syscall_stubs(Box::new(TestStubs));
+    });
+}
+
+// Moves the clock the program reads, a test starts at a fixed time and epoch.
+pub(crate) fn set_clock(unix_timestamp: i64, epoch: u64) {
+    CLOCK.with(|clock| clock.set((unix_timestamp, epoch)));
+}
+
+pub(crate) fn now() -> i64 {
+    CLOCK.with(Cell::get).0
+}
+
+// What the last instruction set as return data, if anything.
+pub(crate) fn return_data() -> Option<Vec<u8>> {
+    RETURN_DATA.with(|data| data.borrow().clone().map(|(_, data)| data))
+}
+
+// Everything the program logged so far in this test.
+pub(crate) fn logs() -> Vec<String> {
+    LOGS.with(|logs| logs.borrow().clone())
+}
+
+// Answers the CPIs to programs other than the system program, a test that doesn't set one
+// panics on those.
+pub(crate) fn on_cpi(handler: impl Fn(&Instruction, &[AccountInfo]) -> ProgramResult + 'static) {
+    CPI_HANDLER.with(|cpi_handler| *cpi_handler.borrow_mut() = Some(Box::new(handler)));
+}
+
+// An account laid out like the runtime serializes it for a program: the data length is
+// stored in the 8 bytes before the data, and there is MAX_PERMITTED_DATA_INCREASE bytes of
+// room after it to grow into.
+pub(crate) struct TestAccount {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub executable: bool,
+    // u64s so the length in front of the data is aligned.
+    buffer: Vec<u64>,
+}
+
+impl TestAccount {
+    pub fn new(owner: Pubkey, lamports: u64, data: &[u8]) -> Self {
+        let words = 1 + (data.len() + MAX_PERMITTED_DATA_INCREASE).div_ceil(8);
+        let mut account = Self {
+            key: Pubkey::new_unique(),
+            owner,
+            lamports,
+            is_signer: false,
+            is_writable: true,
+            executable: false,
+            buffer: vec![0; words],
+        };
+        account.set_data(data);
+        account
+    }
+
+    // A system account holding `lamports`, like a wallet.
+    pub fn wallet(lamports: u64) -> Self {
+        Self::new(system_program::id(), lamports, &[])
+    }
+
+    pub fn signer(mut self) -> Self {
+        self.is_signer = true;
+        self
+    }
+
+    pub fn readonly(mut self) -> Self {
+        self.is_writable = false;
+        self
+    }
+
+    pub fn with_key(mut self, key: Pubkey) -> Self {
+        self.key = key;
+        self
+    }
+
+    fn bytes(&self) -> &[u8] {
+        unsafe {
+            std::slice::from_raw_parts(self.buffer.as_ptr() as *const u8, self.buffer.len() * 8)
+        }
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        unsafe {
+            std::slice::from_raw_parts_mut(
+                self.buffer.as_mut_ptr() as *mut u8,
+                self.buffer.len() * 8,
+            )
+        }
+    }
+
+    pub fn data(&self) -> &[u8] {
+        let len = self.buffer[0] as usize;
+        &self.bytes()[8..8 + len]
+    }
+
+    pub fn set_data(&mut self, data: &[u8]) {
+        assert!(
+            8 + data.len() <= self.buffer.len() * 8,
+            "data too big for the account"
+        );
+        self.buffer[0] = data.len() as u64;
+        self.bytes_mut()[8..8 + data.len()].copy_from_slice(data);
+    }
+
+    pub fn info(&mut self) -> AccountInfo<'_> {
+        let len = self.buffer[0] as usize;
+        let data = unsafe {
+            std::slice::from_raw_parts_mut((self.buffer.as_mut_ptr() as *mut u8).add(8), len)
+        };
+        AccountInfo::new(
+            &self.key,
+            self.is_signer,
+            self.is_writable,
+            &mut self.lamports,
+            data,
+            &self.owner,
+            self.executable,
+            0,
+        )
+    }
+}
+
+// Holds `details`, funded with its rent plus `balance`.
+pub(crate) fn campaign_account(details: CampaignDetails, balance: u64) -> TestAccount {
+    let data = details.try_to_vec().unwrap();
+    let rent = Rent::default().minimum_balance(data.len());
+    TestAccount::new(PROGRAM_ID, rent + balance, &data)
+}
+
+// A campaign of the current version held by `admin`, created now.
+pub(crate) fn campaign(admin: &TestAccount) -> CampaignDetails {
+    CampaignDetails {
+        version: CAMPAIGN_VERSION,
+        admin: admin.key,
+        name: "Campaign".to_string(),
+        created_at: now(),
+        ..CampaignDetails::default()
+    }
+}
+
+pub(crate) fn read_campaign(account: &TestAccount) -> CampaignDetails {
+    CampaignDetails::try_from_slice(account.data()).unwrap()
+}
+
+// The lamports `account` holds above its rent exemption.
+pub(crate) fn balance(account: &TestAccount) -> u64 {
+    account.lamports - Rent::default().minimum_balance(account.data().len())
+}
+
+pub(crate) fn instruction_data(tag: u8, payload: impl BorshSerialize) -> Vec<u8> {
+    let mut data = vec![tag];
+    data.extend(payload.try_to_vec().unwrap());
+    data
+}
+
+// Runs CreateCampaign for `details` on a new account of the right size, funded with its rent
+// plus `balance`.
+pub(crate) fn create_campaign(
+    admin: &mut TestAccount,
+    details: &CampaignDetails,
+    balance: u64,
+) -> Result<TestAccount, ProgramError> {
+    let space = details.try_to_vec().unwrap().len();
+    let rent = Rent::default().minimum_balance(space);
+    let mut writing_account = TestAccount::new(PROGRAM_ID, rent + balance, &vec![0; space]);
+    process(
+        &mut [&mut writing_account, admin],
+        &instruction_data(0, details),
+    )?;
+    Ok(writing_account)
+}
+
+// Donates `amount` lamports from `donator` through a new donator program account.
+pub(crate) fn donate(
+    writing_account: &mut TestAccount,
+    donator: &mut TestAccount,
+    amount: u64,
+) -> ProgramResult {
+    donate_with(writing_account, donator, amount, &mut [])
+}
+
+// Like `donate`, with `extra` accounts passed after the donator.
+pub(crate) fn donate_with(
+    writing_account: &mut TestAccount,
+    donator: &mut TestAccount,
+    amount: u64,
+    extra: &mut [&mut TestAccount],
+) -> ProgramResult {
+    let mut donator_program_account = TestAccount::new(PROGRAM_ID, amount, &[]);
+    let mut accounts: Vec<&mut TestAccount> =
+        vec![writing_account, &mut donator_program_account, donator];
+    accounts.extend(extra.iter_mut().map(|account| &mut **account));
+    process(&mut accounts, &instruction_data(2, ()))
+}
+
+// The system program, for the instructions that create accounts.
+pub(crate) fn system_program_account() -> TestAccount {
+    TestAccount::new(Pubkey::default(), 0, &[])
+        .with_key(system_program::id())
+        .readonly()
+}
+
+// The not yet created PDA of `seed` for the campaign in `writing_account`.
+pub(crate) fn campaign_pda(seed: &[u8], writing_account: &TestAccount) -> TestAccount {
+    let (key, _) = Pubkey::find_program_address(&[seed, writing_account.key.as_ref()], &PROGRAM_ID);
+    TestAccount::wallet(0).with_key(key)
+}
+
+// Runs one instruction on `accounts`, passed in that order.
+pub(crate) fn process(accounts: &mut [&mut TestAccount], data: &[u8]) -> ProgramResult {
+    install_stubs();
+    RETURN_DATA.with(|return_data| *return_data.borrow_mut() = None);
+    let infos: Vec<AccountInfo> = accounts.iter_mut().map(|account| account.info()).collect();
+    process_instruction(&PROGRAM_ID, &infos, data)
+}
@@ -0,0 +1,99 @@
+use solana_program::{program_error::ProgramError, system_program};
+
+use super::{campaign, campaign_account, instruction_data, process, set_clock, TestAccount};
+use crate::{constants::CLOCK_TOLERANCE_SECS, CampaignDetails, CampaignStatus, CrowdfundingError};
+
+const PRUNE: u8 = 11;
+const DEADLINE: i64 = 2_000_000;
+
+fn expired(admin: &TestAccount) -> CampaignDetails {
+    let mut details = campaign(admin);
+    details.deadline = DEADLINE;
+    details
+}
+
+// Prunes a campaign holding `details` after its deadline, by an account that didn't sign.
+fn prune(details: CampaignDetails, admin: &mut TestAccount) -> Result<TestAccount, ProgramError> {
+    let mut writing_account = campaign_account(details, 0);
+    set_clock(DEADLINE + CLOCK_TOLERANCE_SECS + 1, 0);
+    process(
+        &mut [&mut writing_account, admin],
+        &instruction_data(PRUNE, ()),
+    )?;
+    Ok(writing_account)
+}
+
+#[test]
+fn closes_an_expired_empty_campaign() {
+    let mut admin = TestAccount::wallet(0);
+    let details = expired(&admin);
+    let rent = campaign_account(expired(&admin), 0).lamports;
+
+    let writing_account = prune(details, &mut admin).unwrap();
+
+    assert_eq!(writing_account.lamports, 0);
+    assert_eq!(admin.lamports, rent);
+    assert!(writing_account.data().is_empty());
+    assert_eq!(writing_account.owner, system_program::id());
+}
+
+#[test]
+fn rejects_a_funded_campaign() {
+    let mut admin = TestAccount::wallet(0);
+    let mut details = expired(&admin);
+    details.amount_donated = 1;
+
+    assert_eq!(
+        prune(details, &mut admin).err(),
+        Some(CrowdfundingError::CampaignNotPrunable.into())
+    );
+    assert_eq!(admin.lamports, 0);
+}
+
+#[test]
+fn rejects_a_finalized_campaign() {
+    // The recorded outcome is the only trace of the campaign, so it stays even when empty.
+    let mut admin = TestAccount::wallet(0);
+    let mut details = expired(&admin);
+    details.final_status = Some(CampaignStatus::Ended);
+
+    assert_eq!(
+        prune(details, &mut admin).err(),
+        Some(CrowdfundingError::CampaignNotPrunable.into())
+    );
+    assert_eq!(admin.lamports, 0);
+}
+
+#[test]
+fn rejects_an_active_campaign() {
+    let mut admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(expired(&admin), 0);
+    set_clock(DEADLINE + CLOCK_TOLERANCE_SECS, 0);
+
+    assert_eq!(
+        process(
+            &mut [&mut writing_account, &mut admin],
+            &instruction_data(PRUNE, ())
+        ),
+        Err(CrowdfundingError::CampaignNotPrunable.into())
+    );
+
+    let mut details = expired(&admin);
+    details.deadline = 0;
+    assert_eq!(
+        prune(details, &mut admin).err(),
+        Some(CrowdfundingError::CampaignNotPrunable.into())
+    );
+}
+
+#[test]
+fn the_lamports_only_go_to_the_admin() {
+    let admin = TestAccount::wallet(0);
+    let mut other = TestAccount::wallet(0);
+
+    assert_eq!(
+        prune(expired(&admin), &mut other).err(),
+        Some(ProgramError::InvalidAccountData)
+    );
+    assert_eq!(other.lamports, 0);
+}
@@ -0,0 +1,92 @@
+use borsh::BorshDeserialize;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use super::{
+    campaign, campaign_account, campaign_pda, donate_with, instruction_data, process, return_data,
+    system_program_account, TestAccount,
+};
+use crate::constants::{RECENT_DONORS_LEN, RECENT_DONORS_SEED};
+
+const RECENT_DONORS: u8 = 6;
+
+fn read_recent_donors(
+    writing_account: &mut TestAccount,
+    recent_donors_account: &mut TestAccount,
+) -> Vec<Pubkey> {
+    process(
+        &mut [writing_account, recent_donors_account],
+        &instruction_data(RECENT_DONORS, ()),
+    )
+    .unwrap();
+    Vec::<Pubkey>::try_from_slice(&return_data().unwrap()).unwrap()
+}
+
+#[test]
+fn lists_the_latest_donors_newest_first() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut recent_donors_account = campaign_pda(RECENT_DONORS_SEED, &writing_account);
+    let mut system = system_program_account();
+
+    assert!(read_recent_donors(&mut writing_account, &mut recent_donors_account).is_empty());
+
+    let mut donors = Vec::new();
+    for _ in 0..3 {
+        let mut donator = TestAccount::wallet(1_000_000_000).signer();
+        donate_with(
+            &mut writing_account,
+            &mut donator,
+            100,
+            &mut [&mut recent_donors_account, &mut system],
+        )
+        .unwrap();
+        donors.insert(0, donator.key);
+    }
+
+    assert_eq!(
+        read_recent_donors(&mut writing_account, &mut recent_donors_account),
+        donors
+    );
+}
+
+#[test]
+fn evicts_the_oldest_donors() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut recent_donors_account = campaign_pda(RECENT_DONORS_SEED, &writing_account);
+    let mut system = system_program_account();
+
+    let mut donors = Vec::new();
+    for _ in 0..RECENT_DONORS_LEN + 2 {
+        let mut donator = TestAccount::wallet(1_000_000_000).signer();
+        donate_with(
+            &mut writing_account,
+            &mut donator,
+            100,
+            &mut [&mut recent_donors_account, &mut system],
+        )
+        .unwrap();
+        donors.insert(0, donator.key);
+    }
+    donors.truncate(RECENT_DONORS_LEN);
+
+    assert_eq!(
+        read_recent_donors(&mut writing_account, &mut recent_donors_account),
+        donors
+    );
+}
+
+#[test]
+fn rejects_another_account_as_the_list() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut not_the_pda = TestAccount::wallet(0);
+
+    assert_eq!(
+        process(
+            &mut [&mut writing_account, &mut not_the_pda],
+            &instruction_data(RECENT_DONORS, ()),
+        ),
+        Err(ProgramError::InvalidSeeds)
+    );
+}
@@ -0,0 +1,45 @@
+use solana_program::program_error::ProgramError;
+
+use super::{
+    campaign, campaign_account, instruction_data, logs, process, return_data, TestAccount,
+};
+use crate::CrowdfundingError;
+
+const RECONCILE: u8 = 20;
+
+// Reconciles a campaign that was given 1_000 lamports and paid 400 out, holding `balance`
+// lamports above its rent, and returns the surplus.
+fn reconcile(balance: u64) -> Result<u64, ProgramError> {
+    let mut details = campaign(&TestAccount::wallet(0));
+    details.amount_donated = 1_000;
+    details.total_withdrawn = 400;
+    let mut writing_account = campaign_account(details, balance);
+    process(
+        &mut [&mut writing_account],
+        &instruction_data(RECONCILE, ()),
+    )?;
+    Ok(u64::from_le_bytes(
+        return_data().unwrap().try_into().unwrap(),
+    ))
+}
+
+#[test]
+fn a_balance_matching_the_books_has_no_surplus() {
+    assert_eq!(reconcile(600), Ok(0));
+}
+
+#[test]
+fn returns_the_surplus_of_a_bigger_balance() {
+    assert_eq!(reconcile(750), Ok(150));
+}
+
+#[test]
+fn detects_missing_lamports() {
+    assert_eq!(
+        reconcile(599),
+        Err(CrowdfundingError::BalanceShortfall.into())
+    );
+    assert!(logs()
+        .iter()
+        .any(|log| log.starts_with("CF:BALANCE_SHORTFALL")));
+}
@@ -0,0 +1,105 @@
+use solana_program::program_error::ProgramError;
+
+use super::{
+    balance, campaign, campaign_account, instruction_data, logs, process, read_campaign,
+    TestAccount, PROGRAM_ID,
+};
+use crate::{CampaignDetails, CrowdfundingError};
+
+const DONATE_WITH_REFERRER: u8 = 25;
+
+fn referral_campaign(admin: &TestAccount, referral_bps: u16) -> CampaignDetails {
+    let mut details = campaign(admin);
+    details.referral_bps = referral_bps;
+    details
+}
+
+fn donate_with_referrer(
+    writing_account: &mut TestAccount,
+    donator: &mut TestAccount,
+    referrer: &mut TestAccount,
+    amount: u64,
+) -> Result<(), ProgramError> {
+    let mut donator_program_account = TestAccount::new(PROGRAM_ID, amount, &[]);
+    process(
+        &mut [
+            writing_account,
+            &mut donator_program_account,
+            donator,
+            referrer,
+        ],
+        &instruction_data(DONATE_WITH_REFERRER, ()),
+    )
+}
+
+#[test]
+fn splits_the_donation_with_the_referrer() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(referral_campaign(&admin, 500), 0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut referrer = TestAccount::wallet(0);
+
+    donate_with_referrer(&mut writing_account, &mut donator, &mut referrer, 10_000).unwrap();
+
+    assert_eq!(referrer.lamports, 500);
+    assert_eq!(balance(&writing_account), 9_500);
+    assert_eq!(read_campaign(&writing_account).amount_donated, 9_500);
+    assert!(logs().contains(&format!("CF:REFERRAL {} 500", referrer.key)));
+}
+
+#[test]
+fn the_referral_rounds_down() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(referral_campaign(&admin, 500), 0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut referrer = TestAccount::wallet(0);
+
+    donate_with_referrer(&mut writing_account, &mut donator, &mut referrer, 39).unwrap();
+
+    assert_eq!(referrer.lamports, 1);
+    assert_eq!(read_campaign(&writing_account).amount_donated, 38);
+}
+
+#[test]
+fn without_referral_bps_everything_goes_to_the_campaign() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(referral_campaign(&admin, 0), 0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut referrer = TestAccount::wallet(0);
+
+    donate_with_referrer(&mut writing_account, &mut donator, &mut referrer, 10_000).unwrap();
+
+    assert_eq!(referrer.lamports, 0);
+    assert_eq!(read_campaign(&writing_account).amount_donated, 10_000);
+}
+
+#[test]
+fn the_campaign_its_admin_and_the_donator_can_not_refer() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(referral_campaign(&admin, 500), 0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let campaign_key = writing_account.key;
+    let donator_key = donator.key;
+
+    for key in [campaign_key, admin.key, donator_key] {
+        let mut referrer = TestAccount::wallet(0).with_key(key);
+        assert_eq!(
+            donate_with_referrer(&mut writing_account, &mut donator, &mut referrer, 10_000),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+    assert_eq!(read_campaign(&writing_account).amount_donated, 0);
+}
+
+#[test]
+fn the_referrer_must_be_writable() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(referral_campaign(&admin, 500), 0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut referrer = TestAccount::wallet(0).readonly();
+
+    assert_eq!(
+        donate_with_referrer(&mut writing_account, &mut donator, &mut referrer, 10_000),
+        Err(CrowdfundingError::AccountNotWritable.into())
+    );
+}
@@ -0,0 +1,68 @@
+use solana_program::{entrypoint::MAX_PERMITTED_DATA_INCREASE, rent::Rent};
+
+use super::{install_stubs, system_program_account, TestAccount, PROGRAM_ID};
+use crate::{resize_account, CrowdfundingError};
+
+// A program account of `len` bytes with exactly its rent, and a payer for the growth.
+fn accounts(len: usize) -> (TestAccount, TestAccount) {
+    install_stubs();
+    let account = TestAccount::new(
+        PROGRAM_ID,
+        Rent::default().minimum_balance(len),
+        &vec![1; len],
+    );
+    (account, TestAccount::wallet(1_000_000_000).signer())
+}
+
+#[test]
+fn rejects_growing_past_the_limit_in_one_instruction() {
+    let (mut account, mut payer) = accounts(100);
+    let mut system = system_program_account();
+
+    assert_eq!(
+        resize_account(
+            &account.info(),
+            &payer.info(),
+            &system.info(),
+            100 + MAX_PERMITTED_DATA_INCREASE + 1,
+        ),
+        Err(CrowdfundingError::ReallocTooLarge.into())
+    );
+    assert_eq!(account.data().len(), 100);
+    assert_eq!(payer.lamports, 1_000_000_000);
+}
+
+#[test]
+fn grows_up_to_the_limit_with_the_payer_paying_the_rent() {
+    let (mut account, mut payer) = accounts(100);
+    let mut system = system_program_account();
+    let new_len = 100 + MAX_PERMITTED_DATA_INCREASE;
+
+    resize_account(&account.info(), &payer.info(), &system.info(), new_len).unwrap();
+
+    let rent = Rent::default();
+    assert_eq!(account.data().len(), new_len);
+    assert_eq!(account.data()[..100], [1; 100]);
+    assert_eq!(account.lamports, rent.minimum_balance(new_len));
+    assert_eq!(
+        payer.lamports,
+        1_000_000_000 - (rent.minimum_balance(new_len) - rent.minimum_balance(100))
+    );
+}
+
+#[test]
+fn shrinking_returns_the_rent_to_the_payer() {
+    let (mut account, mut payer) = accounts(MAX_PERMITTED_DATA_INCREASE * 2);
+    let mut system = system_program_account();
+
+    resize_account(&account.info(), &payer.info(), &system.info(), 10).unwrap();
+
+    let rent = Rent::default();
+    assert_eq!(account.data().len(), 10);
+    assert_eq!(account.lamports, rent.minimum_balance(10));
+    assert_eq!(
+        payer.lamports,
+        1_000_000_000 + rent.minimum_balance(MAX_PERMITTED_DATA_INCREASE * 2)
+            - rent.minimum_balance(10)
+    );
+}
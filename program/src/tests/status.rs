@@ -0,0 +1,76 @@
+use super::{
+    campaign, campaign_account, instruction_data, now, process, return_data, set_clock, TestAccount,
+};
+use crate::{constants::CLOCK_TOLERANCE_SECS, CampaignDetails, CampaignStatus};
+
+// The status code returned by the Status instruction at `at`.
+fn status_at(details: CampaignDetails, at: i64) -> u8 {
+    let mut writing_account = campaign_account(details, 0);
+    set_clock(at, 0);
+    process(&mut [&mut writing_account], &instruction_data(10, ())).unwrap();
+    let data = return_data().unwrap();
+    assert_eq!(data.len(), 1);
+    data[0]
+}
+
+// The clock moves between the checks, so the deadline is fixed instead of following it.
+const DEADLINE: i64 = 2_000_000;
+
+fn with_goal(goal: u64, amount_donated: u64) -> CampaignDetails {
+    let mut details = campaign(&TestAccount::wallet(0));
+    details.deadline = DEADLINE;
+    details.goal = goal;
+    details.amount_donated = amount_donated;
+    details
+}
+
+#[test]
+fn active_until_the_deadline_and_its_tolerance() {
+    for at in [now(), DEADLINE, DEADLINE + CLOCK_TOLERANCE_SECS] {
+        assert_eq!(
+            status_at(with_goal(1_000, 0), at),
+            CampaignStatus::Active as u8
+        );
+    }
+}
+
+#[test]
+fn a_campaign_without_deadline_stays_active() {
+    let mut details = with_goal(1_000, 0);
+    details.deadline = 0;
+    assert_eq!(status_at(details, i64::MAX), CampaignStatus::Active as u8);
+}
+
+#[test]
+fn ends_with_the_outcome_of_the_goal() {
+    let after = DEADLINE + CLOCK_TOLERANCE_SECS + 1;
+
+    assert_eq!(
+        status_at(with_goal(0, 500), after),
+        CampaignStatus::Ended as u8
+    );
+    assert_eq!(
+        status_at(with_goal(1_000, 1_000), after),
+        CampaignStatus::Succeeded as u8
+    );
+    assert_eq!(
+        status_at(with_goal(1_000, 999), after),
+        CampaignStatus::Failed as u8
+    );
+}
+
+#[test]
+fn a_finalized_campaign_keeps_its_recorded_status() {
+    let mut details = with_goal(1_000, 0);
+    details.final_status = Some(CampaignStatus::Succeeded);
+
+    assert_eq!(status_at(details, now()), CampaignStatus::Succeeded as u8);
+}
+
+#[test]
+fn the_codes_are_pinned() {
+    assert_eq!(CampaignStatus::Active as u8, 0);
+    assert_eq!(CampaignStatus::Ended as u8, 1);
+    assert_eq!(CampaignStatus::Succeeded as u8, 2);
+    assert_eq!(CampaignStatus::Failed as u8, 3);
+}
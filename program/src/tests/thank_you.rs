@@ -0,0 +1,89 @@
+use solana_program::{program_error::ProgramError, rent::Rent};
+
+use super::{
+    balance, campaign, campaign_account, instruction_data, process, read_campaign, return_data,
+    system_program_account, TestAccount,
+};
+use crate::{constants::MAX_THANK_YOU_LEN, ThankYouRequest};
+
+const SET_THANK_YOU: u8 = 12;
+const GET_THANK_YOU: u8 = 13;
+
+fn set_thank_you(
+    writing_account: &mut TestAccount,
+    admin: &mut TestAccount,
+    thank_you: &str,
+) -> Result<(), ProgramError> {
+    let mut system = system_program_account();
+    process(
+        &mut [writing_account, admin, &mut system],
+        &instruction_data(
+            SET_THANK_YOU,
+            ThankYouRequest {
+                thank_you: thank_you.to_string(),
+            },
+        ),
+    )
+}
+
+fn get_thank_you(writing_account: &mut TestAccount) -> String {
+    process(&mut [writing_account], &instruction_data(GET_THANK_YOU, ())).unwrap();
+    String::from_utf8(return_data().unwrap()).unwrap()
+}
+
+#[test]
+fn sets_and_reads_the_note() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    assert_eq!(get_thank_you(&mut writing_account), "");
+
+    set_thank_you(&mut writing_account, &mut admin, "Merci à tous !").unwrap();
+    assert_eq!(get_thank_you(&mut writing_account), "Merci à tous !");
+    assert_eq!(read_campaign(&writing_account).thank_you, "Merci à tous !");
+    // The admin paid for the longer account.
+    assert_eq!(balance(&writing_account), 0);
+
+    set_thank_you(&mut writing_account, &mut admin, "Thanks").unwrap();
+    assert_eq!(get_thank_you(&mut writing_account), "Thanks");
+    assert_eq!(
+        writing_account.lamports,
+        Rent::default().minimum_balance(writing_account.data().len())
+    );
+}
+
+#[test]
+fn rejects_a_note_too_long_or_with_control_characters() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+
+    assert_eq!(
+        set_thank_you(
+            &mut writing_account,
+            &mut admin,
+            &"a".repeat(MAX_THANK_YOU_LEN + 1)
+        ),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    assert_eq!(
+        set_thank_you(&mut writing_account, &mut admin, "Thanks\nfor nothing"),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    set_thank_you(
+        &mut writing_account,
+        &mut admin,
+        &"a".repeat(MAX_THANK_YOU_LEN),
+    )
+    .unwrap();
+}
+
+#[test]
+fn only_the_admin_sets_the_note() {
+    let admin = TestAccount::wallet(0);
+    let mut other = TestAccount::wallet(1_000_000_000).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+
+    assert_eq!(
+        set_thank_you(&mut writing_account, &mut other, "Thanks"),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
@@ -0,0 +1,138 @@
+use std::{cell::RefCell, rc::Rc};
+
+use borsh::BorshDeserialize;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use super::{
+    campaign, campaign_account, create_campaign, donate, donate_with, instruction_data, on_cpi,
+    process, read_campaign, TestAccount, PROGRAM_ID,
+};
+use crate::{constants::MIN_INITIAL_FUNDING, ValidatedOperation, ValidationRequest};
+
+const WITHDRAW: u8 = 1;
+const REJECTED: ProgramError = ProgramError::Custom(99);
+
+// A mock validator program that approves when `approve` is set and records what it was
+// asked.
+fn mock_validator(approve: bool) -> (TestAccount, Rc<RefCell<Vec<ValidationRequest>>>) {
+    let key = Pubkey::new_unique();
+    let requests = Rc::new(RefCell::new(Vec::new()));
+    let recorded = requests.clone();
+    on_cpi(move |instruction, _| {
+        assert_eq!(instruction.program_id, key);
+        recorded
+            .borrow_mut()
+            .push(ValidationRequest::try_from_slice(&instruction.data).unwrap());
+        if approve {
+            Ok(())
+        } else {
+            Err(REJECTED)
+        }
+    });
+    let mut program = TestAccount::new(Pubkey::default(), 1, &[])
+        .with_key(key)
+        .readonly();
+    program.executable = true;
+    (program, requests)
+}
+
+fn validated_campaign(admin: &TestAccount, validator: &TestAccount, balance: u64) -> TestAccount {
+    let mut details = campaign(admin);
+    details.validator_program = Some(validator.key);
+    details.amount_donated = balance;
+    campaign_account(details, balance)
+}
+
+fn withdraw(
+    writing_account: &mut TestAccount,
+    admin: &mut TestAccount,
+    validator: &mut TestAccount,
+    amount: u64,
+) -> Result<(), ProgramError> {
+    process(
+        &mut [writing_account, admin, validator],
+        &instruction_data(WITHDRAW, amount),
+    )
+}
+
+#[test]
+fn an_approving_validator_lets_the_operations_through() {
+    let (mut validator, requests) = mock_validator(true);
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = validated_campaign(&admin, &validator, 0);
+
+    donate_with(
+        &mut writing_account,
+        &mut donator,
+        300,
+        &mut [&mut validator],
+    )
+    .unwrap();
+    withdraw(&mut writing_account, &mut admin, &mut validator, 100).unwrap();
+
+    assert_eq!(read_campaign(&writing_account).amount_donated, 300);
+    assert_eq!(admin.lamports, 100);
+    let requests = requests.borrow();
+    assert_eq!(requests.len(), 2);
+    assert!(matches!(requests[0].operation, ValidatedOperation::Donate));
+    assert_eq!(requests[0].actor, donator.key);
+    assert_eq!(requests[0].amount, 300);
+    assert!(matches!(
+        requests[1].operation,
+        ValidatedOperation::Withdraw
+    ));
+    assert_eq!(requests[1].actor, admin.key);
+    assert_eq!(requests[1].amount, 100);
+}
+
+#[test]
+fn a_rejecting_validator_fails_the_operations() {
+    let (mut validator, requests) = mock_validator(false);
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = validated_campaign(&admin, &validator, 1_000);
+
+    assert_eq!(
+        donate_with(
+            &mut writing_account,
+            &mut donator,
+            300,
+            &mut [&mut validator]
+        ),
+        Err(REJECTED)
+    );
+    assert_eq!(
+        withdraw(&mut writing_account, &mut admin, &mut validator, 100),
+        Err(REJECTED)
+    );
+    assert_eq!(admin.lamports, 0);
+    assert_eq!(read_campaign(&writing_account).amount_donated, 1_000);
+    assert_eq!(requests.borrow().len(), 2);
+}
+
+#[test]
+fn the_validator_program_must_be_passed() {
+    let (validator, requests) = mock_validator(true);
+    let admin = TestAccount::wallet(0);
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut writing_account = validated_campaign(&admin, &validator, 0);
+
+    assert_eq!(
+        donate(&mut writing_account, &mut donator, 300),
+        Err(ProgramError::NotEnoughAccountKeys)
+    );
+    assert!(requests.borrow().is_empty());
+}
+
+#[test]
+fn a_campaign_can_not_validate_itself() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.validator_program = Some(PROGRAM_ID);
+
+    assert_eq!(
+        create_campaign(&mut admin, &details, MIN_INITIAL_FUNDING).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+}
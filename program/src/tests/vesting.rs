@@ -0,0 +1,159 @@
+use solana_program::program_error::ProgramError;
+
+use super::{
+    balance, campaign, campaign_account, create_campaign, donate, instruction_data, process,
+    read_campaign, set_clock, TestAccount,
+};
+use crate::{
+    constants::{MAX_VESTING_DURATION_SECS, MIN_INITIAL_FUNDING},
+    CrowdfundingError,
+};
+
+const CREATE: u8 = 0;
+const WITHDRAW: u8 = 1;
+const WITHDRAW_OR_MAX: u8 = 7;
+const CREATED_AT: i64 = 1_000_000;
+const DURATION: i64 = 1_000;
+const DONATED: u64 = 10_000;
+
+fn vesting_campaign(admin: &TestAccount) -> TestAccount {
+    let mut details = campaign(admin);
+    details.created_at = CREATED_AT;
+    details.vesting_duration = DURATION;
+    details.amount_donated = DONATED;
+    campaign_account(details, DONATED)
+}
+
+fn withdraw_at(
+    elapsed: i64,
+    tag: u8,
+    writing_account: &mut TestAccount,
+    admin: &mut TestAccount,
+    amount: u64,
+) -> Result<(), ProgramError> {
+    set_clock(CREATED_AT + elapsed, 0);
+    process(
+        &mut [writing_account, admin],
+        &instruction_data(tag, amount),
+    )
+}
+
+#[test]
+fn releases_the_donations_linearly() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = vesting_campaign(&admin);
+
+    // Nothing is released when the campaign is created.
+    assert_eq!(
+        withdraw_at(0, WITHDRAW, &mut writing_account, &mut admin, 1),
+        Err(CrowdfundingError::ExceedsVestedAmount.into())
+    );
+
+    // A quarter of the way in a quarter is released, one lamport more is too much.
+    assert_eq!(
+        withdraw_at(250, WITHDRAW, &mut writing_account, &mut admin, 2_501),
+        Err(CrowdfundingError::ExceedsVestedAmount.into())
+    );
+    withdraw_at(250, WITHDRAW, &mut writing_account, &mut admin, 2_500).unwrap();
+
+    // Halfway through only what was released since the last withdrawal is left.
+    assert_eq!(
+        withdraw_at(500, WITHDRAW, &mut writing_account, &mut admin, 2_501),
+        Err(CrowdfundingError::ExceedsVestedAmount.into())
+    );
+    withdraw_at(500, WITHDRAW, &mut writing_account, &mut admin, 2_500).unwrap();
+
+    // Once the schedule is over the rest is released, and nothing vests past it.
+    withdraw_at(
+        DURATION * 2,
+        WITHDRAW,
+        &mut writing_account,
+        &mut admin,
+        5_000,
+    )
+    .unwrap();
+    assert_eq!(admin.lamports, DONATED);
+    assert_eq!(balance(&writing_account), 0);
+    assert_eq!(read_campaign(&writing_account).total_withdrawn, DONATED);
+}
+
+#[test]
+fn or_max_stops_at_the_vested_amount() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = vesting_campaign(&admin);
+
+    withdraw_at(
+        100,
+        WITHDRAW_OR_MAX,
+        &mut writing_account,
+        &mut admin,
+        DONATED,
+    )
+    .unwrap();
+    assert_eq!(admin.lamports, 1_000);
+}
+
+#[test]
+fn more_than_the_balance_is_still_insufficient_funds() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = vesting_campaign(&admin);
+
+    assert_eq!(
+        withdraw_at(
+            DURATION,
+            WITHDRAW,
+            &mut writing_account,
+            &mut admin,
+            DONATED + 1
+        ),
+        Err(ProgramError::InsufficientFunds)
+    );
+}
+
+#[test]
+fn the_duration_stays_in_range() {
+    let mut admin = TestAccount::wallet(0).signer();
+
+    for (vesting_duration, valid) in [
+        (-1, false),
+        (MAX_VESTING_DURATION_SECS, true),
+        (MAX_VESTING_DURATION_SECS + 1, false),
+    ] {
+        let mut details = campaign(&admin);
+        details.vesting_duration = vesting_duration;
+        assert_eq!(
+            create_campaign(&mut admin, &details, MIN_INITIAL_FUNDING).is_ok(),
+            valid,
+            "vesting_duration {}",
+            vesting_duration
+        );
+    }
+}
+
+#[test]
+fn a_live_campaign_can_not_be_created_again() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut donator = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.vesting_duration = DURATION;
+    let mut writing_account = create_campaign(&mut admin, &details, MIN_INITIAL_FUNDING).unwrap();
+    donate(&mut writing_account, &mut donator, DONATED).unwrap();
+    let data = writing_account.data().to_vec();
+
+    // The same payload without vesting has the same size, it would reset the books and
+    // release everything right away.
+    let mut details = campaign(&admin);
+    details.vesting_duration = 0;
+    assert_eq!(
+        process(
+            &mut [&mut writing_account, &mut admin],
+            &instruction_data(CREATE, &details),
+        ),
+        Err(ProgramError::AccountAlreadyInitialized)
+    );
+    assert_eq!(writing_account.data(), data);
+    assert_eq!(
+        withdraw_at(0, WITHDRAW, &mut writing_account, &mut admin, DONATED),
+        Err(CrowdfundingError::ExceedsVestedAmount.into())
+    );
+}
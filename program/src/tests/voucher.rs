@@ -0,0 +1,261 @@
+use solana_program::{program_error::ProgramError, pubkey::Pubkey, rent::Rent, system_program};
+
+use super::{
+    balance, campaign, campaign_account, instruction_data, process, read_campaign, set_clock,
+    system_program_account, TestAccount, PROGRAM_ID,
+};
+use crate::{
+    constants::{CLOCK_TOLERANCE_SECS, VOUCHER_SEED},
+    CreateVoucherRequest, CrowdfundingError, VoucherRequest,
+};
+
+const DONATE: u8 = 2;
+const PRUNE: u8 = 11;
+const CREATE_VOUCHER: u8 = 15;
+const REDEEM_VOUCHER: u8 = 16;
+const DONATE_WITH_REFERRER: u8 = 25;
+const CLOSE_VOUCHER: u8 = 28;
+const VOUCHER_ID: u64 = 42;
+const AMOUNT: u64 = 5_000;
+const DEADLINE: i64 = 2_000_000;
+
+// The not yet created PDA of voucher `voucher_id` for the campaign in `writing_account`.
+fn voucher_pda(writing_account: &TestAccount, voucher_id: u64) -> TestAccount {
+    let (key, _) = Pubkey::find_program_address(
+        &[
+            VOUCHER_SEED,
+            writing_account.key.as_ref(),
+            &voucher_id.to_le_bytes(),
+        ],
+        &PROGRAM_ID,
+    );
+    TestAccount::wallet(0).with_key(key)
+}
+
+fn create_voucher(
+    writing_account: &mut TestAccount,
+    admin: &mut TestAccount,
+    voucher: &mut TestAccount,
+) -> Result<(), ProgramError> {
+    process(
+        &mut [
+            writing_account,
+            admin,
+            voucher,
+            &mut system_program_account(),
+        ],
+        &instruction_data(
+            CREATE_VOUCHER,
+            CreateVoucherRequest {
+                voucher_id: VOUCHER_ID,
+                amount: AMOUNT,
+            },
+        ),
+    )
+}
+
+fn redeem_voucher(
+    writing_account: &mut TestAccount,
+    voucher: &mut TestAccount,
+    donator: &mut TestAccount,
+) -> Result<(), ProgramError> {
+    process(
+        &mut [writing_account, voucher, donator],
+        &instruction_data(
+            REDEEM_VOUCHER,
+            VoucherRequest {
+                voucher_id: VOUCHER_ID,
+            },
+        ),
+    )
+}
+
+fn close_voucher(
+    writing_account: &mut TestAccount,
+    admin: &mut TestAccount,
+    voucher: &mut TestAccount,
+) -> Result<(), ProgramError> {
+    process(
+        &mut [writing_account, admin, voucher],
+        &instruction_data(
+            CLOSE_VOUCHER,
+            VoucherRequest {
+                voucher_id: VOUCHER_ID,
+            },
+        ),
+    )
+}
+
+// A campaign with a funded voucher, and the admin that funded it.
+fn funded_voucher() -> (TestAccount, TestAccount, TestAccount) {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut voucher = voucher_pda(&writing_account, VOUCHER_ID);
+    create_voucher(&mut writing_account, &mut admin, &mut voucher).unwrap();
+    (writing_account, admin, voucher)
+}
+
+#[test]
+fn the_admin_funds_a_voucher() {
+    let (_, admin, voucher) = funded_voucher();
+
+    assert_eq!(voucher.owner, PROGRAM_ID);
+    assert_eq!(balance(&voucher), AMOUNT);
+    assert_eq!(admin.lamports, 1_000_000_000 - voucher.lamports);
+}
+
+#[test]
+fn a_voucher_is_redeemed_once() {
+    let (mut writing_account, _, mut voucher) = funded_voucher();
+    let mut donator = TestAccount::wallet(0).signer();
+    let voucher_rent = Rent::default().minimum_balance(voucher.data().len());
+
+    redeem_voucher(&mut writing_account, &mut voucher, &mut donator).unwrap();
+    assert_eq!(read_campaign(&writing_account).amount_donated, AMOUNT);
+    assert_eq!(balance(&writing_account), AMOUNT);
+    // The voucher keeps its rent so it stays around marked as redeemed.
+    assert_eq!(voucher.lamports, voucher_rent);
+
+    let mut other = TestAccount::wallet(0).signer();
+    assert_eq!(
+        redeem_voucher(&mut writing_account, &mut voucher, &mut other),
+        Err(CrowdfundingError::VoucherAlreadyRedeemed.into())
+    );
+    assert_eq!(read_campaign(&writing_account).amount_donated, AMOUNT);
+    assert_eq!(voucher.lamports, voucher_rent);
+}
+
+#[test]
+fn a_voucher_id_is_only_funded_once() {
+    let (mut writing_account, mut admin, mut voucher) = funded_voucher();
+
+    assert_eq!(
+        create_voucher(&mut writing_account, &mut admin, &mut voucher),
+        Err(ProgramError::AccountAlreadyInitialized)
+    );
+}
+
+#[test]
+fn a_voucher_only_credits_its_campaign() {
+    let (_, admin, mut voucher) = funded_voucher();
+    let mut other_campaign = campaign_account(campaign(&admin), 0);
+    let mut donator = TestAccount::wallet(0).signer();
+
+    // The voucher isn't the PDA of the other campaign.
+    assert_eq!(
+        redeem_voucher(&mut other_campaign, &mut voucher, &mut donator),
+        Err(ProgramError::InvalidSeeds)
+    );
+    assert_eq!(read_campaign(&other_campaign).amount_donated, 0);
+}
+
+#[test]
+fn only_the_admin_funds_a_voucher() {
+    let admin = TestAccount::wallet(0);
+    let mut other = TestAccount::wallet(1_000_000_000).signer();
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut voucher = voucher_pda(&writing_account, VOUCHER_ID);
+
+    assert_eq!(
+        create_voucher(&mut writing_account, &mut other, &mut voucher),
+        Err(ProgramError::InvalidAccountData)
+    );
+    assert_eq!(voucher.owner, system_program::id());
+}
+
+#[test]
+fn the_admin_takes_back_an_unredeemed_voucher() {
+    let (mut writing_account, mut admin, mut voucher) = funded_voucher();
+
+    close_voucher(&mut writing_account, &mut admin, &mut voucher).unwrap();
+    assert_eq!(admin.lamports, 1_000_000_000);
+    assert_eq!(voucher.lamports, 0);
+    assert_eq!(voucher.owner, system_program::id());
+}
+
+#[test]
+fn a_redeemed_voucher_can_not_be_closed() {
+    let (mut writing_account, mut admin, mut voucher) = funded_voucher();
+    let mut donator = TestAccount::wallet(0).signer();
+    redeem_voucher(&mut writing_account, &mut voucher, &mut donator).unwrap();
+
+    assert_eq!(
+        close_voucher(&mut writing_account, &mut admin, &mut voucher),
+        Err(CrowdfundingError::VoucherAlreadyRedeemed.into())
+    );
+}
+
+#[test]
+fn a_voucher_can_not_be_swept_as_a_donator_program_account() {
+    let (_, _, mut voucher) = funded_voucher();
+    let voucher_lamports = voucher.lamports;
+    let mut attacker = TestAccount::wallet(0).signer();
+    let mut attacker_campaign = campaign_account(campaign(&attacker), 0);
+
+    for tag in [DONATE, DONATE_WITH_REFERRER] {
+        let mut referrer = TestAccount::wallet(0);
+        assert_eq!(
+            process(
+                &mut [
+                    &mut attacker_campaign,
+                    &mut voucher,
+                    &mut attacker,
+                    &mut referrer
+                ],
+                &instruction_data(tag, ()),
+            ),
+            Err(ProgramError::InvalidAccountData),
+            "tag {}",
+            tag
+        );
+    }
+    assert_eq!(voucher.lamports, voucher_lamports);
+    assert_eq!(balance(&attacker_campaign), 0);
+}
+
+#[test]
+fn the_campaign_can_not_donate_to_itself() {
+    let admin = TestAccount::wallet(0);
+    let mut writing_account = campaign_account(campaign(&admin), 0);
+    let mut itself = TestAccount::new(PROGRAM_ID, 100, &[]).with_key(writing_account.key);
+    let mut donator = TestAccount::wallet(0).signer();
+
+    assert_eq!(
+        process(
+            &mut [&mut writing_account, &mut itself, &mut donator],
+            &instruction_data(DONATE, ()),
+        ),
+        Err(ProgramError::InvalidArgument)
+    );
+}
+
+#[test]
+fn the_funding_admin_closes_a_voucher_left_by_a_pruned_campaign() {
+    let mut admin = TestAccount::wallet(1_000_000_000).signer();
+    let mut details = campaign(&admin);
+    details.deadline = DEADLINE;
+    let mut writing_account = campaign_account(details, 0);
+    let mut voucher = voucher_pda(&writing_account, VOUCHER_ID);
+    create_voucher(&mut writing_account, &mut admin, &mut voucher).unwrap();
+
+    set_clock(DEADLINE + CLOCK_TOLERANCE_SECS + 1, 0);
+    process(
+        &mut [&mut writing_account, &mut admin],
+        &instruction_data(PRUNE, ()),
+    )
+    .unwrap();
+    assert_eq!(writing_account.owner, system_program::id());
+    let admin_lamports = admin.lamports;
+    let voucher_lamports = voucher.lamports;
+
+    let mut other = TestAccount::wallet(0).signer();
+    assert_eq!(
+        close_voucher(&mut writing_account, &mut other, &mut voucher),
+        Err(ProgramError::InvalidAccountData)
+    );
+
+    close_voucher(&mut writing_account, &mut admin, &mut voucher).unwrap();
+    assert_eq!(admin.lamports, admin_lamports + voucher_lamports);
+    assert_eq!(voucher.lamports, 0);
+    assert_eq!(voucher.owner, system_program::id());
+}
@@ -0,0 +1,280 @@
+use solana_program::program_error::ProgramError;
+
+use super::{
+    balance, campaign, campaign_account, instruction_data, logs, process, read_campaign,
+    return_data, TestAccount,
+};
+use crate::{
+    constants::{DUST_THRESHOLD, MAX_WITHDRAW_NOTE_LEN},
+    CrowdfundingError, WithdrawWithNoteRequest,
+};
+
+const WITHDRAW: u8 = 1;
+const WITHDRAW_OR_MAX: u8 = 7;
+const WITHDRAW_WITH_NOTE: u8 = 24;
+
+fn withdraw(
+    tag: u8,
+    writing_account: &mut TestAccount,
+    admin: &mut TestAccount,
+    amount: u64,
+) -> Result<u64, ProgramError> {
+    process(
+        &mut [writing_account, admin],
+        &instruction_data(tag, amount),
+    )?;
+    Ok(u64::from_le_bytes(
+        return_data().unwrap().try_into().unwrap(),
+    ))
+}
+
+fn funded_campaign(admin: &TestAccount, balance: u64) -> TestAccount {
+    let mut details = campaign(admin);
+    details.amount_donated = balance;
+    campaign_account(details, balance)
+}
+
+#[test]
+fn or_max_withdraws_everything_available_when_asked_too_much() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = funded_campaign(&admin, 1_000);
+
+    assert_eq!(
+        withdraw(WITHDRAW_OR_MAX, &mut writing_account, &mut admin, 1_500),
+        Ok(1_000)
+    );
+    assert_eq!(balance(&writing_account), 0);
+    assert_eq!(admin.lamports, 1_000);
+    assert_eq!(read_campaign(&writing_account).total_withdrawn, 1_000);
+}
+
+#[test]
+fn or_max_withdraws_the_exact_amount_when_available() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = funded_campaign(&admin, 1_000);
+
+    assert_eq!(
+        withdraw(WITHDRAW_OR_MAX, &mut writing_account, &mut admin, 400),
+        Ok(400)
+    );
+    assert_eq!(
+        withdraw(WITHDRAW_OR_MAX, &mut writing_account, &mut admin, 600),
+        Ok(600)
+    );
+    assert_eq!(balance(&writing_account), 0);
+    assert_eq!(admin.lamports, 1_000);
+}
+
+#[test]
+fn withdraw_stays_strict() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = funded_campaign(&admin, 1_000);
+
+    assert_eq!(
+        withdraw(WITHDRAW, &mut writing_account, &mut admin, 1_001),
+        Err(ProgramError::InsufficientFunds)
+    );
+    assert_eq!(balance(&writing_account), 1_000);
+
+    assert_eq!(
+        withdraw(WITHDRAW, &mut writing_account, &mut admin, 1_000),
+        Ok(1_000)
+    );
+    assert_eq!(admin.lamports, 1_000);
+}
+
+// 10% of the balance, rent included, stays in the campaign.
+#[test]
+fn the_rent_buffer_reduces_what_can_be_withdrawn() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut details = campaign(&admin);
+    details.amount_donated = 1_000_000;
+    details.rent_buffer_bps = 1_000;
+    let mut writing_account = campaign_account(details, 1_000_000);
+    let buffer = writing_account.lamports / 10;
+
+    assert_eq!(
+        withdraw(
+            WITHDRAW,
+            &mut writing_account,
+            &mut admin,
+            1_000_000 - buffer + 1
+        ),
+        Err(ProgramError::InsufficientFunds)
+    );
+    assert_eq!(
+        withdraw(WITHDRAW_OR_MAX, &mut writing_account, &mut admin, 1_000_000),
+        Ok(1_000_000 - buffer)
+    );
+    assert_eq!(balance(&writing_account), buffer);
+}
+
+#[test]
+fn without_a_rent_buffer_only_the_rent_stays() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = funded_campaign(&admin, 1_000_000);
+
+    assert_eq!(
+        withdraw(WITHDRAW_OR_MAX, &mut writing_account, &mut admin, u64::MAX),
+        Ok(1_000_000)
+    );
+}
+
+fn sweeping_campaign(admin: &TestAccount, sweep_dust: bool) -> TestAccount {
+    let mut details = campaign(admin);
+    details.amount_donated = 100_000;
+    details.sweep_dust = sweep_dust;
+    campaign_account(details, 100_000)
+}
+
+#[test]
+fn sweeps_the_dust_a_withdrawal_would_leave() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = sweeping_campaign(&admin, true);
+
+    // Leaving one lamport under the threshold takes it along.
+    assert_eq!(
+        withdraw(
+            WITHDRAW,
+            &mut writing_account,
+            &mut admin,
+            100_000 - DUST_THRESHOLD + 1
+        ),
+        Ok(100_000)
+    );
+    assert_eq!(balance(&writing_account), 0);
+    assert_eq!(admin.lamports, 100_000);
+    assert_eq!(read_campaign(&writing_account).total_withdrawn, 100_000);
+}
+
+#[test]
+fn leaves_the_threshold_and_above() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = sweeping_campaign(&admin, true);
+
+    assert_eq!(
+        withdraw(
+            WITHDRAW,
+            &mut writing_account,
+            &mut admin,
+            100_000 - DUST_THRESHOLD
+        ),
+        Ok(100_000 - DUST_THRESHOLD)
+    );
+    assert_eq!(balance(&writing_account), DUST_THRESHOLD);
+}
+
+#[test]
+fn keeps_the_dust_without_sweep_dust() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = sweeping_campaign(&admin, false);
+
+    assert_eq!(
+        withdraw(WITHDRAW, &mut writing_account, &mut admin, 99_999),
+        Ok(99_999)
+    );
+    assert_eq!(balance(&writing_account), 1);
+}
+
+fn withdraw_with_note(
+    writing_account: &mut TestAccount,
+    admin: &mut TestAccount,
+    amount: u64,
+    note: &str,
+) -> Result<(), ProgramError> {
+    process(
+        &mut [writing_account, admin],
+        &instruction_data(
+            WITHDRAW_WITH_NOTE,
+            WithdrawWithNoteRequest {
+                amount,
+                note: note.to_string(),
+            },
+        ),
+    )
+}
+
+fn notes() -> Vec<String> {
+    logs()
+        .into_iter()
+        .filter(|log| log.starts_with("CF:WITHDRAW_NOTE"))
+        .collect()
+}
+
+#[test]
+fn logs_the_note_of_each_withdrawal() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = funded_campaign(&admin, 1_000);
+
+    withdraw_with_note(&mut writing_account, &mut admin, 300, "Venue deposit").unwrap();
+    withdraw_with_note(&mut writing_account, &mut admin, 200, "Printing").unwrap();
+
+    assert_eq!(
+        notes(),
+        [
+            "CF:WITHDRAW_NOTE 300 Venue deposit",
+            "CF:WITHDRAW_NOTE 200 Printing"
+        ]
+    );
+    assert_eq!(admin.lamports, 500);
+}
+
+#[test]
+fn rejects_an_empty_too_long_or_control_character_note() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = funded_campaign(&admin, 1_000);
+
+    for note in [
+        String::new(),
+        "n".repeat(MAX_WITHDRAW_NOTE_LEN + 1),
+        "Line\nbreak".to_string(),
+    ] {
+        assert_eq!(
+            withdraw_with_note(&mut writing_account, &mut admin, 100, &note),
+            Err(ProgramError::InvalidInstructionData),
+            "{:?}",
+            note
+        );
+    }
+    assert_eq!(admin.lamports, 0);
+    assert!(notes().is_empty());
+
+    let longest = "n".repeat(MAX_WITHDRAW_NOTE_LEN);
+    withdraw_with_note(&mut writing_account, &mut admin, 100, &longest).unwrap();
+}
+
+#[test]
+fn a_failed_withdrawal_logs_no_note() {
+    let mut admin = TestAccount::wallet(0).signer();
+    let mut writing_account = funded_campaign(&admin, 1_000);
+
+    assert_eq!(
+        withdraw_with_note(&mut writing_account, &mut admin, 1_001, "Too much"),
+        Err(ProgramError::InsufficientFunds)
+    );
+    assert!(notes().is_empty());
+}
+
+#[test]
+fn the_admin_must_sign_and_be_writable() {
+    let admin = TestAccount::wallet(0).signer();
+    let mut writing_account = funded_campaign(&admin, 1_000);
+
+    let mut read_only = TestAccount::wallet(0)
+        .with_key(admin.key)
+        .signer()
+        .readonly();
+    assert_eq!(
+        withdraw(WITHDRAW, &mut writing_account, &mut read_only, 100),
+        Err(CrowdfundingError::AccountNotWritable.into())
+    );
+
+    let mut not_signing = TestAccount::wallet(0).with_key(admin.key);
+    assert_eq!(
+        withdraw(WITHDRAW, &mut writing_account, &mut not_signing, 100),
+        Err(ProgramError::IncorrectProgramId)
+    );
+
+    assert_eq!(balance(&writing_account), 1_000);
+    assert_eq!(read_campaign(&writing_account).total_withdrawn, 0);
+}
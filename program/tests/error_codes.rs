@@ -0,0 +1,59 @@
+// Clients match on the custom error codes, so each one is pinned here. A failure in this
+// file means a code moved, which breaks every deployed client.
+
+use program::CrowdfundingError::{self, *};
+use solana_program::program_error::ProgramError;
+
+const CODES: [(CrowdfundingError, u32); 19] = [
+    (AccountSizeMismatch, 0),
+    (InsufficientInitialFunding, 1),
+    (InvalidAccountData, 2),
+    (InvalidInstruction, 3),
+    (CampaignEnded, 4),
+    (ReallocTooLarge, 5),
+    (CampaignImmutable, 6),
+    (DonorBlocked, 7),
+    (BlockListFull, 8),
+    (CampaignNotPrunable, 9),
+    (UnsupportedInstructionVersion, 10),
+    (DonationTooLarge, 11),
+    (VoucherAlreadyRedeemed, 12),
+    (ExceedsVestedAmount, 13),
+    (CampaignNotExpired, 14),
+    (InvalidAmount, 15),
+    (BalanceShortfall, 16),
+    (AccountNotWritable, 17),
+    (CampaignFinalized, 18),
+];
+
+#[test]
+fn every_error_keeps_its_code() {
+    for (error, code) in CODES {
+        assert_eq!(
+            ProgramError::from(error),
+            ProgramError::Custom(code),
+            "{:?}",
+            error
+        );
+    }
+}
+
+#[test]
+fn every_code_converts_back_to_its_error() {
+    for (error, code) in CODES {
+        assert_eq!(CrowdfundingError::try_from(code), Ok(error));
+    }
+}
+
+#[test]
+fn unknown_codes_are_rejected() {
+    let next = CODES.len() as u32;
+    assert_eq!(
+        CrowdfundingError::try_from(next),
+        Err(ProgramError::Custom(next))
+    );
+    assert_eq!(
+        CrowdfundingError::try_from(u32::MAX),
+        Err(ProgramError::Custom(u32::MAX))
+    );
+}
@@ -0,0 +1,189 @@
+// The rent checks of create_campaign and withdraw at their exact boundaries, with the real
+// Rent sysvar of the test bank. Off-by-one rent handling would let a campaign drop below
+// rent exemption, or refuse a withdrawal that leaves exactly enough.
+
+use borsh::BorshSerialize;
+use program::{
+    constants::{CAMPAIGN_VERSION, MIN_INITIAL_FUNDING},
+    process_instruction, CampaignDetails, CrowdfundingError,
+};
+use solana_program_test::{processor, tokio, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+fn campaign(admin: Pubkey) -> CampaignDetails {
+    CampaignDetails {
+        version: CAMPAIGN_VERSION,
+        admin,
+        name: "Rent".to_string(),
+        ..CampaignDetails::default()
+    }
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|err| err.unwrap())
+}
+
+// Creates the campaign account holding `lamports` and runs CreateCampaign on it.
+async fn create_with_lamports(lamports: u64) -> Result<(), TransactionError> {
+    let program_id = Pubkey::new_unique();
+    let mut context = ProgramTest::new("program", program_id, processor!(process_instruction))
+        .start_with_context()
+        .await;
+
+    let writing_account = Keypair::new();
+    let details = campaign(context.payer.pubkey());
+    let space = details.try_to_vec().unwrap().len();
+    let mut data = vec![0];
+    data.extend(details.try_to_vec().unwrap());
+
+    let instructions = [
+        system_instruction::create_account(
+            &context.payer.pubkey(),
+            &writing_account.pubkey(),
+            lamports,
+            space as u64,
+            &program_id,
+        ),
+        Instruction::new_with_bytes(
+            program_id,
+            &data,
+            vec![
+                AccountMeta::new(writing_account.pubkey(), false),
+                AccountMeta::new(context.payer.pubkey(), true),
+            ],
+        ),
+    ];
+    send(&mut context, &instructions, &[&writing_account]).await
+}
+
+fn campaign_rent() -> u64 {
+    let space = campaign(Pubkey::new_unique()).try_to_vec().unwrap().len();
+    Rent::default().minimum_balance(space)
+}
+
+#[tokio::test]
+async fn create_accepts_exactly_rent_plus_initial_funding() {
+    let minimum = campaign_rent() + MIN_INITIAL_FUNDING;
+    assert_eq!(create_with_lamports(minimum).await, Ok(()));
+}
+
+#[tokio::test]
+async fn create_rejects_one_lamport_below_rent_plus_initial_funding() {
+    let minimum = campaign_rent() + MIN_INITIAL_FUNDING;
+    assert_eq!(
+        create_with_lamports(minimum - 1).await,
+        Err(TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(CrowdfundingError::InsufficientInitialFunding as u32)
+        ))
+    );
+}
+
+// Exactly the rent exemption is enough to be rent exempt, but a campaign also needs its
+// initial funding on top of it.
+#[tokio::test]
+async fn create_with_exactly_rent_needs_initial_funding() {
+    assert_eq!(
+        create_with_lamports(campaign_rent()).await,
+        Err(TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(CrowdfundingError::InsufficientInitialFunding as u32)
+        ))
+    );
+    assert_eq!(
+        create_with_lamports(campaign_rent() - 1).await,
+        Err(TransactionError::InstructionError(
+            1,
+            InstructionError::InsufficientFunds
+        ))
+    );
+}
+
+// Withdraws `amount` from a campaign holding its rent plus 1 SOL.
+async fn withdraw(amount: u64) -> (Result<(), TransactionError>, u64) {
+    let program_id = Pubkey::new_unique();
+    let admin = Keypair::new();
+    let writing_account = Pubkey::new_unique();
+    let data = campaign(admin.pubkey()).try_to_vec().unwrap();
+    let rent = Rent::default().minimum_balance(data.len());
+
+    let mut program_test = ProgramTest::new("program", program_id, processor!(process_instruction));
+    program_test.add_account(
+        writing_account,
+        Account {
+            lamports: rent + 1_000_000_000,
+            data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let mut data = vec![1];
+    data.extend(amount.to_le_bytes());
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &data,
+        vec![
+            AccountMeta::new(writing_account, false),
+            AccountMeta::new(admin.pubkey(), true),
+        ],
+    );
+    let result = send(&mut context, &[instruction], &[&admin]).await;
+    let balance = context
+        .banks_client
+        .get_balance(writing_account)
+        .await
+        .unwrap();
+    (result, balance - rent)
+}
+
+#[tokio::test]
+async fn withdraw_can_leave_exactly_rent() {
+    assert_eq!(withdraw(1_000_000_000).await, (Ok(()), 0));
+}
+
+#[tokio::test]
+async fn withdraw_can_not_leave_one_lamport_less_than_rent() {
+    assert_eq!(
+        withdraw(1_000_000_001).await,
+        (
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::InsufficientFunds
+            )),
+            1_000_000_000
+        )
+    );
+}